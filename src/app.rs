@@ -1,7 +1,7 @@
 use std::{
     fs,
     io::{BufRead, BufReader, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
@@ -9,6 +9,13 @@ use std::{
 #[cfg(target_os = "windows")]
 use std::io::Read;
 
+#[cfg(target_os = "linux")]
+use futures::StreamExt;
+#[cfg(target_os = "linux")]
+use gpui::AsyncWindowContext;
+#[cfg(target_os = "linux")]
+use serde_json::{Value, json};
+
 use gpui::{
     App, AsyncApp, Bounds, Context, CursorStyle, Decorations, Entity, FocusHandle, Focusable,
     HitboxBehavior, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PathPromptOptions,
@@ -18,18 +25,26 @@ use gpui::{
 
 use crate::{
     app_state::AppState,
-    client_manager::{self, ClientManagerState, ClientRelease},
+    client_capabilities,
+    client_manager::{
+        self, ClientManagerState, ClientRelease, DownloadProgress, PinnedClient, ReleaseChannel,
+    },
     components::*,
     configuration::*,
-    connection_state::ConnectionState,
+    connection_state::{ConnectionState, ReconnectPolicy},
     log_panel::LogPanel,
     process_log::ProcessLog,
+    release_notes::release_notes,
+    secret_store,
     system::{self, *},
     text_area::TextArea,
     text_input::TextInput,
     theme::*,
 };
 
+#[cfg(target_os = "linux")]
+use crate::control_socket::{self, ControlCommand, ControlEvents, ControlRequest};
+
 use system::{
     dns::{self, DnsBackend},
     proxy::{self as proxy, ProxyBackend},
@@ -71,14 +86,26 @@ pub struct AppInitialization {
     pub password_input: Entity<TextInput>,
     pub certificate_input: Entity<TextArea>,
     pub dns_upstreams_input: Entity<TextInput>,
+    pub included_routes_input: Entity<TextInput>,
+    pub excluded_routes_input: Entity<TextInput>,
+    pub exclusions_input: Entity<TextInput>,
+    pub client_version_input: Entity<TextInput>,
+    pub proxy_listen_input: Entity<TextInput>,
+    pub vault_passphrase_input: Entity<TextInput>,
+    pub vault_confirmation_input: Entity<TextInput>,
+    pub release_url_input: Entity<TextInput>,
+    pub github_token_input: Entity<TextInput>,
     pub has_ipv6: bool,
     pub skip_verification: bool,
     pub upstream_protocol: String,
     pub upstream_fallback_protocol: String,
+    pub vpn_mode: String,
     pub anti_dpi: bool,
     pub killswitch_enabled: bool,
     pub post_quantum_group_enabled: bool,
     pub dns_enabled: bool,
+    pub proxy_authentication: bool,
+    pub reconnect_policy: ReconnectPolicy,
     pub configuration_path: PathBuf,
     pub system_services: Arc<dyn SystemServices>,
     pub log_panel: Entity<LogPanel>,
@@ -92,6 +119,9 @@ pub struct AppInitialization {
 
 const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
 
+/// Choices of the "Keep Older Versions" setting; `None` keeps everything.
+const KEEP_CLIENT_VERSION_OPTIONS: [Option<usize>; 4] = [None, Some(1), Some(2), Some(3)];
+
 fn session_timestamp() -> String {
     chrono::Local::now().format("%Y-%m-%d_%H-%M-%S").to_string()
 }
@@ -103,14 +133,30 @@ pub struct TrustTunnelApp {
     password_input: Entity<TextInput>,
     certificate_input: Entity<TextArea>,
     dns_upstreams_input: Entity<TextInput>,
+    included_routes_input: Entity<TextInput>,
+    excluded_routes_input: Entity<TextInput>,
+    exclusions_input: Entity<TextInput>,
+    client_version_input: Entity<TextInput>,
+    proxy_listen_input: Entity<TextInput>,
+    vault_passphrase_input: Entity<TextInput>,
+    vault_confirmation_input: Entity<TextInput>,
+    release_url_input: Entity<TextInput>,
+    github_token_input: Entity<TextInput>,
     has_ipv6: bool,
     skip_verification: bool,
     upstream_protocol: String,
     upstream_fallback_protocol: String,
+    vpn_mode: String,
     anti_dpi: bool,
     killswitch_enabled: bool,
     post_quantum_group_enabled: bool,
     dns_enabled: bool,
+    proxy_listen_address: String,
+    proxy_authentication: bool,
+    proxy_listener: Option<ProxyListener>,
+    reconnect_policy: ReconnectPolicy,
+    reconnect_attempt: u32,
+    reconnect_due: Option<Instant>,
     tunnel_mode: TunnelMode,
     system_services: Arc<dyn SystemServices>,
     connection_state: ConnectionState,
@@ -126,10 +172,14 @@ pub struct TrustTunnelApp {
     fallback_none_focus_handle: FocusHandle,
     fallback_http2_focus_handle: FocusHandle,
     fallback_http3_focus_handle: FocusHandle,
+    vpn_mode_general_focus_handle: FocusHandle,
+    vpn_mode_selective_focus_handle: FocusHandle,
     mode_tun_focus_handle: FocusHandle,
     mode_system_proxy_focus_handle: FocusHandle,
     mode_proxy_focus_handle: FocusHandle,
     dns_enabled_focus_handle: FocusHandle,
+    proxy_authentication_focus_handle: FocusHandle,
+    auto_reconnect_focus_handle: FocusHandle,
     killswitch_focus_handle: FocusHandle,
     post_quantum_focus_handle: FocusHandle,
     connect_button_focus_handle: FocusHandle,
@@ -137,16 +187,32 @@ pub struct TrustTunnelApp {
     import_focus_handle: FocusHandle,
     add_focus_handle: FocusHandle,
     remove_focus_handle: FocusHandle,
+    vault_unlock_focus_handle: FocusHandle,
     process_log: Arc<Mutex<ProcessLog>>,
     log_panel: Entity<LogPanel>,
     log_scroll_handle: ScrollHandle,
     configuration_scroll_handle: ScrollHandle,
-    configuration_scroll_anchors: [ScrollAnchor; 10],
+    configuration_scroll_anchors: [ScrollAnchor; 15],
     log_file: Option<Arc<Mutex<fs::File>>>,
     proxy_overrides: Vec<Box<dyn ProxyBackend>>,
     dns_override: Option<Box<dyn DnsBackend>>,
     binary_path: String,
     binary_found: bool,
+    /// Binary of the current or last session; differs from `binary_path`
+    /// when the credential pins a client version.
+    session_binary_path: String,
+    /// Set while `--help` of the session binary is probed before connecting;
+    /// cleared by a disconnect so the pending connection is dropped.
+    capability_probe_pending: bool,
+    /// Bumped per credential rescan so only the latest result is applied.
+    credential_scan_generation: u64,
+    /// Pinned client version that is published but not installed; a
+    /// download button for it is shown under the status.
+    offered_client_download: Option<String>,
+    offered_download_focus_handle: FocusHandle,
+    /// Per-field validation messages, shown under the inputs after a
+    /// connect attempt found problems.
+    field_errors: Vec<FieldError>,
     poll_tick: u32,
     disconnecting_since: Option<Instant>,
     stored_credentials: Vec<StoredCredential>,
@@ -155,13 +221,28 @@ pub struct TrustTunnelApp {
     active_tab: ActiveTab,
     client_manager_state: Arc<Mutex<ClientManagerState>>,
     client_version_focus_handles: Vec<FocusHandle>,
+    client_upgrade_focus_handle: FocusHandle,
+    client_prune_focus_handle: FocusHandle,
+    client_channel_focus_handle: FocusHandle,
+    client_keep_focus_handles: Vec<FocusHandle>,
+    client_release_source_focus_handle: FocusHandle,
+    release_source_error: Option<String>,
+    client_import_focus_handle: FocusHandle,
     client_download_focus_handle: FocusHandle,
     client_remove_focus_handle: FocusHandle,
     client_scroll_handle: ScrollHandle,
+    #[cfg(target_os = "linux")]
+    control_events: ControlEvents,
+    #[cfg(target_os = "linux")]
+    published_state: Option<Value>,
 }
 
 impl TrustTunnelApp {
-    pub fn new(initialization: AppInitialization, context: &mut Context<Self>) -> Self {
+    pub fn new(
+        initialization: AppInitialization,
+        window: &mut Window,
+        context: &mut Context<Self>,
+    ) -> Self {
         let mut stored_credentials = initialization.stored_credentials;
         let mut selected_credential = initialization.selected_credential;
 
@@ -183,8 +264,44 @@ impl TrustTunnelApp {
             ActiveTab::Client
         };
 
-        if initial_tab == ActiveTab::Client {
-            client_manager::start_fetch_releases(initialization.client_manager_state.clone());
+        // Fetched on every start so a newer client can be flagged in the titlebar.
+        client_manager::start_fetch_releases(initialization.client_manager_state.clone());
+
+        let proxy_listen_address = initialization.proxy_listen_input.read(context).text();
+
+        let process_log = Arc::new(Mutex::new(ProcessLog::new()));
+
+        #[cfg(not(target_os = "linux"))]
+        let _ = window;
+        #[cfg(target_os = "linux")]
+        let control_events = ControlEvents::default();
+        #[cfg(target_os = "linux")]
+        {
+            let events = control_events.clone();
+            if let Ok(mut locked_log) = process_log.lock() {
+                locked_log.set_line_listener(Arc::new(move |line| {
+                    events.emit(json!({ "event": "log", "line": line }));
+                }));
+            }
+            if let Some(mut receiver) = control_socket::start(control_events.clone()) {
+                context
+                    .spawn_in(
+                        window,
+                        async move |this: WeakEntity<Self>, context: &mut AsyncWindowContext| {
+                            while let Some(request) = receiver.next().await {
+                                if this
+                                    .update_in(context, |this, window, context| {
+                                        this.handle_control_request(request, window, context)
+                                    })
+                                    .is_err()
+                                {
+                                    break;
+                                }
+                            }
+                        },
+                    )
+                    .detach();
+            }
         }
 
         let configuration_scroll_handle = ScrollHandle::new();
@@ -198,14 +315,30 @@ impl TrustTunnelApp {
             password_input: initialization.password_input,
             certificate_input: initialization.certificate_input,
             dns_upstreams_input: initialization.dns_upstreams_input,
+            included_routes_input: initialization.included_routes_input,
+            excluded_routes_input: initialization.excluded_routes_input,
+            exclusions_input: initialization.exclusions_input,
+            client_version_input: initialization.client_version_input,
+            proxy_listen_input: initialization.proxy_listen_input,
+            vault_passphrase_input: initialization.vault_passphrase_input,
+            vault_confirmation_input: initialization.vault_confirmation_input,
+            release_url_input: initialization.release_url_input,
+            github_token_input: initialization.github_token_input,
             has_ipv6: initialization.has_ipv6,
             skip_verification: initialization.skip_verification,
             upstream_protocol: initialization.upstream_protocol,
             upstream_fallback_protocol: initialization.upstream_fallback_protocol,
+            vpn_mode: initialization.vpn_mode,
             anti_dpi: initialization.anti_dpi,
             killswitch_enabled: initialization.killswitch_enabled,
             post_quantum_group_enabled: initialization.post_quantum_group_enabled,
             dns_enabled: initialization.dns_enabled,
+            proxy_listen_address,
+            proxy_authentication: initialization.proxy_authentication,
+            proxy_listener: None,
+            reconnect_policy: initialization.reconnect_policy,
+            reconnect_attempt: 0,
+            reconnect_due: None,
             tunnel_mode: initialization.tunnel_mode,
             system_services: initialization.system_services,
             connection_state: ConnectionState::Disconnected,
@@ -221,10 +354,14 @@ impl TrustTunnelApp {
             fallback_none_focus_handle: context.focus_handle(),
             fallback_http2_focus_handle: context.focus_handle(),
             fallback_http3_focus_handle: context.focus_handle(),
+            vpn_mode_general_focus_handle: context.focus_handle(),
+            vpn_mode_selective_focus_handle: context.focus_handle(),
             mode_tun_focus_handle: context.focus_handle(),
             mode_system_proxy_focus_handle: context.focus_handle(),
             mode_proxy_focus_handle: context.focus_handle(),
             dns_enabled_focus_handle: context.focus_handle(),
+            proxy_authentication_focus_handle: context.focus_handle(),
+            auto_reconnect_focus_handle: context.focus_handle(),
             killswitch_focus_handle: context.focus_handle(),
             post_quantum_focus_handle: context.focus_handle(),
             connect_button_focus_handle: context.focus_handle(),
@@ -234,7 +371,8 @@ impl TrustTunnelApp {
             import_focus_handle: context.focus_handle(),
             add_focus_handle: context.focus_handle(),
             remove_focus_handle: context.focus_handle(),
-            process_log: Arc::new(Mutex::new(ProcessLog::new())),
+            vault_unlock_focus_handle: context.focus_handle(),
+            process_log,
             log_panel: initialization.log_panel,
             log_scroll_handle: ScrollHandle::new(),
             configuration_scroll_handle,
@@ -244,6 +382,12 @@ impl TrustTunnelApp {
             dns_override: None,
             binary_path: initialization.binary_path,
             binary_found: initialization.binary_found,
+            session_binary_path: String::new(),
+            capability_probe_pending: false,
+            credential_scan_generation: 0,
+            offered_client_download: None,
+            offered_download_focus_handle: context.focus_handle(),
+            field_errors: Vec::new(),
             poll_tick: 0,
             disconnecting_since: None,
             stored_credentials,
@@ -252,9 +396,23 @@ impl TrustTunnelApp {
             active_tab: initial_tab,
             client_manager_state: initialization.client_manager_state,
             client_version_focus_handles: Vec::new(),
+            client_upgrade_focus_handle: context.focus_handle(),
+            client_prune_focus_handle: context.focus_handle(),
+            client_channel_focus_handle: context.focus_handle(),
+            client_keep_focus_handles: KEEP_CLIENT_VERSION_OPTIONS
+                .iter()
+                .map(|_| context.focus_handle())
+                .collect(),
+            client_release_source_focus_handle: context.focus_handle(),
+            release_source_error: None,
+            client_import_focus_handle: context.focus_handle(),
             client_download_focus_handle: context.focus_handle(),
             client_remove_focus_handle: context.focus_handle(),
             client_scroll_handle: ScrollHandle::new(),
+            #[cfg(target_os = "linux")]
+            control_events,
+            #[cfg(target_os = "linux")]
+            published_state: None,
         }
     }
 
@@ -604,6 +762,7 @@ impl TrustTunnelApp {
         match self.connection_state {
             ConnectionState::Connecting => self.poll_connecting(context),
             ConnectionState::Connected => self.poll_connected(context),
+            ConnectionState::Reconnecting(_) => self.poll_reconnecting(context),
             ConnectionState::Disconnecting => self.poll_disconnecting(context),
             _ => {}
         }
@@ -627,6 +786,26 @@ impl TrustTunnelApp {
             cleanup_elevated_files();
         }
         self.child_process = None;
+
+        let unexpected = match self.connection_state {
+            ConnectionState::Connected => true,
+            ConnectionState::Connecting => self.reconnect_attempt > 0,
+            _ => false,
+        };
+        if unexpected && !matches!(exit.code, Some(126) | Some(127)) {
+            // The TUN interface is gone with the client, so DNS has to be re-applied
+            // by `transition_to_connected`; the system proxy stays pointed at the
+            // listener, which the next attempt reuses.
+            if let Some(mut dns) = self.dns_override.take() {
+                log::info!("[poll] restoring DNS via {} after client exit", dns.name());
+                dns.clear();
+            }
+            if self.schedule_reconnect(format!("Client exited unexpectedly ({exit})")) {
+                context.notify();
+                return;
+            }
+        }
+
         if !self.proxy_overrides.is_empty() {
             log::info!("[poll] restoring system proxy after client exit");
             proxy::clear_all(&mut self.proxy_overrides);
@@ -660,7 +839,7 @@ impl TrustTunnelApp {
                 format!(
                     "Binary '{}' not found. Install TrustTunnel client:\n  \
                      https://github.com/TrustTunnel/TrustTunnelClient",
-                    self.binary_path,
+                    self.session_binary_path,
                 )
             } else {
                 format!("Client exited with code {code}")
@@ -670,17 +849,37 @@ impl TrustTunnelApp {
             self.connection_state = ConnectionState::Error(format!("Exited ({code})"));
             self.status_detail = detail_message;
         }
+        if self.reconnect_attempt > 0 {
+            self.status_detail.push_str(&format!(
+                "\nGave up after {} reconnect attempts",
+                self.reconnect_attempt,
+            ));
+            self.reconnect_attempt = 0;
+        }
         context.notify();
     }
 
     fn poll_connecting(&mut self, context: &mut Context<Self>) {
+        if self.capability_probe_pending {
+            return;
+        }
         let Ok(locked_log) = self.process_log.lock() else {
             return;
         };
 
-        if locked_log.error.is_some() {
+        if let Some(ref error) = locked_log.error {
+            let error = error.clone();
             drop(locked_log);
 
+            if self.reconnect_attempt > 0 {
+                self.abandon_session_for_reconnect();
+                if self.schedule_reconnect(format!("Reconnect failed: {error}")) {
+                    context.notify();
+                    return;
+                }
+                self.reconnect_attempt = 0;
+            }
+
             if let Some(child) = self.cleanup_child() {
                 self.kill_child_background(child);
             }
@@ -704,11 +903,25 @@ impl TrustTunnelApp {
 
     fn transition_to_connected(&mut self, context: &mut Context<Self>) {
         self.connection_state = ConnectionState::Connected;
+        if self.reconnect_attempt > 0 {
+            log::info!(
+                "[reconnect] reconnected after {} attempt(s)",
+                self.reconnect_attempt
+            );
+            self.reconnect_attempt = 0;
+        }
+
+        let proxy_listener = self.proxy_listener.clone().unwrap_or_default();
+        let proxy_address = proxy_listener.address;
+        let proxy_url = proxy_listener.url();
 
         let mut proxy_detail = String::new();
         if self.tunnel_mode.sets_system_proxy() && self.proxy_overrides.is_empty() {
-            let (host, port) = parse_host_port(PROXY_LISTEN_ADDRESS);
-            let (backends, detail) = proxy::set_all(&host, port);
+            let (backends, detail) = proxy::set_all(
+                &proxy_listener.host(),
+                proxy_listener.port(),
+                proxy_listener.credentials.as_ref(),
+            );
             proxy_detail = detail;
             self.proxy_overrides = backends;
         }
@@ -751,8 +964,14 @@ impl TrustTunnelApp {
             TunnelMode::SystemProxy => {
                 let mut lines = format!(
                     "System proxy active — all apps route through VPN\n\
-                     SOCKS5 on {PROXY_LISTEN_ADDRESS}"
+                     SOCKS5 on {proxy_address}"
                 );
+                if let Some(ref credentials) = proxy_listener.credentials {
+                    lines.push_str(&format!(
+                        "\nUsername: {}  Password: {}",
+                        credentials.username, credentials.password,
+                    ));
+                }
                 if !proxy_detail.is_empty() {
                     lines.push_str(&format!("\n{proxy_detail}"));
                 }
@@ -760,28 +979,125 @@ impl TrustTunnelApp {
                 lines
             }
             TunnelMode::Proxy => {
-                if cfg!(target_os = "windows") {
+                let mut lines = if cfg!(target_os = "windows") {
                     format!(
-                        "SOCKS5 proxy on {PROXY_LISTEN_ADDRESS}\n\
-                         PowerShell: $env:ALL_PROXY=\"socks5://{PROXY_LISTEN_ADDRESS}\"\n\
-                         CMD: set ALL_PROXY=socks5://{PROXY_LISTEN_ADDRESS}\n\
-                         Firefox: Settings → Network → SOCKS5 Host: 127.0.0.1  Port: 1080\n\
-                         Chromium: --proxy-server=\"socks5://{PROXY_LISTEN_ADDRESS}\""
+                        "SOCKS5 proxy on {proxy_address}\n\
+                         PowerShell: $env:ALL_PROXY=\"{proxy_url}\"\n\
+                         CMD: set ALL_PROXY={proxy_url}\n\
+                         Firefox: Settings → Network → SOCKS5 Host: {}  Port: {}\n\
+                         Chromium: --proxy-server=\"socks5://{proxy_address}\"",
+                        proxy_listener.host(),
+                        proxy_listener.port(),
                     )
                 } else {
                     format!(
-                        "SOCKS5 proxy on {PROXY_LISTEN_ADDRESS}\n\
-                         Terminal: export ALL_PROXY=\"socks5://{PROXY_LISTEN_ADDRESS}\"\n\
-                         Firefox: Settings → Network → SOCKS5 Host: 127.0.0.1  Port: 1080\n\
-                         Chromium: --proxy-server=\"socks5://{PROXY_LISTEN_ADDRESS}\""
+                        "SOCKS5 proxy on {proxy_address}\n\
+                         Terminal: export ALL_PROXY=\"{proxy_url}\"\n\
+                         Firefox: Settings → Network → SOCKS5 Host: {}  Port: {}\n\
+                         Chromium: --proxy-server=\"socks5://{proxy_address}\"",
+                        proxy_listener.host(),
+                        proxy_listener.port(),
                     )
+                };
+                if let Some(ref credentials) = proxy_listener.credentials {
+                    lines.push_str(&format!(
+                        "\nUsername: {}  Password: {}\n\
+                         Chromium does not support SOCKS5 authentication",
+                        credentials.username, credentials.password,
+                    ));
                 }
+                lines
             }
         };
         context.notify();
     }
 
     fn poll_connected(&mut self, context: &mut Context<Self>) {
+        if self.reconnect_policy.enabled {
+            let post_connect_error = self
+                .process_log
+                .lock()
+                .ok()
+                .and_then(|locked_log| locked_log.post_connect_error.clone());
+            if let Some(error) = post_connect_error {
+                self.abandon_session_for_reconnect();
+                if !self.schedule_reconnect(format!("Connection lost: {error}")) {
+                    if let Some(child) = self.cleanup_child() {
+                        self.kill_child_background(child);
+                    }
+                    self.connection_state = ConnectionState::Error("Connection lost".into());
+                    self.status_detail = error;
+                }
+            }
+        }
+        context.notify();
+    }
+
+    /// Stops the current client without touching the system proxy, so the next
+    /// attempt can take over the same listener.
+    fn abandon_session_for_reconnect(&mut self) {
+        if let Some(mut dns) = self.dns_override.take() {
+            log::info!("[reconnect] restoring DNS via {}", dns.name());
+            dns.clear();
+        }
+        if let Some(child) = self.child_process.take() {
+            #[cfg(target_os = "windows")]
+            if child.is_elevated() {
+                cleanup_elevated_files();
+            }
+            self.kill_child_background(child);
+        }
+    }
+
+    /// Returns false when auto-reconnect is off or the attempts are used up.
+    fn schedule_reconnect(&mut self, reason: String) -> bool {
+        let policy = self.reconnect_policy;
+        if !policy.enabled || self.reconnect_attempt >= policy.max_attempts {
+            return false;
+        }
+
+        self.reconnect_attempt += 1;
+        let delay = policy.delay(self.reconnect_attempt);
+        log::warn!(
+            "[reconnect] {reason} — attempt {}/{} in {}s",
+            self.reconnect_attempt,
+            policy.max_attempts,
+            delay.as_secs(),
+        );
+
+        self.connection_state = ConnectionState::Reconnecting(self.reconnect_attempt);
+        self.reconnect_due = Some(Instant::now() + delay);
+        self.status_detail = format!(
+            "{reason}\nReconnecting in {}s (attempt {}/{}), click to cancel",
+            delay.as_secs(),
+            self.reconnect_attempt,
+            policy.max_attempts,
+        );
+        true
+    }
+
+    fn poll_reconnecting(&mut self, context: &mut Context<Self>) {
+        let due = self.reconnect_due.is_none_or(|due| Instant::now() >= due);
+        if !due {
+            context.notify();
+            return;
+        }
+        self.reconnect_due = None;
+
+        log::info!(
+            "━━━ RECONNECT (attempt {}/{}, mode={}) ━━━",
+            self.reconnect_attempt,
+            self.reconnect_policy.max_attempts,
+            self.tunnel_mode.label(),
+        );
+        if !self.spawn_client_process(self.tunnel_mode, context)
+            && !self.schedule_reconnect("Failed to restart client".into())
+        {
+            if let Some(child) = self.cleanup_child() {
+                self.kill_child_background(child);
+            }
+            self.reconnect_attempt = 0;
+        }
         context.notify();
     }
 
@@ -827,6 +1143,9 @@ impl TrustTunnelApp {
             context.notify();
             return;
         }
+        if !stored.credential.password_ref.is_empty() {
+            secret_store::remove(&stored.credential.password_ref);
+        }
 
         self.stored_credentials.remove(index);
         self.credential_focus_handles.remove(index);
@@ -899,22 +1218,7 @@ impl TrustTunnelApp {
                 };
 
                 if let Err(update_error) = this.update(context, |this, context| {
-                    let saved_state = AppState::load();
-                    this.stored_credentials = scan_credentials(&credentials_path);
-                    crate::app_state::apply_saved_order(
-                        &mut this.stored_credentials,
-                        &saved_state.credential_order,
-                    );
-                    let found_index = this
-                        .stored_credentials
-                        .iter()
-                        .position(|entry| entry.path == destination);
-                    if let Some(selected_index) = found_index {
-                        this.select_credential(selected_index, context);
-                    }
-                    this.sync_credential_focus_handles(context);
-                    this.save_app_state();
-                    context.notify();
+                    this.rescan_credentials(&destination, context);
                 }) {
                     log::warn!(
                         "[credentials] failed to update state after import: {update_error}"
@@ -924,6 +1228,116 @@ impl TrustTunnelApp {
             .detach();
     }
 
+    /// Copies `path` into the credentials directory and selects it. During a
+    /// session the import is kept but the active credential stays selected.
+    #[cfg(target_os = "linux")]
+    fn import_credential_path(
+        &mut self,
+        path: &Path,
+        context: &mut Context<Self>,
+    ) -> Result<(), String> {
+        log::info!("[credentials] adding file: {}", path.display());
+        if self.connection_state.is_active() {
+            let destination = add_credential_file(path, &credentials_directory())?;
+            log::info!(
+                "[credentials] imported {} during a session, keeping the active credential",
+                destination.display()
+            );
+            let selected = self
+                .selected_credential
+                .and_then(|index| self.stored_credentials.get(index))
+                .map(|stored| stored.path.clone())
+                .unwrap_or_default();
+            self.rescan_credentials(&selected, context);
+            return Ok(());
+        }
+        self.save_draft_credential(context);
+        let destination = add_credential_file(path, &credentials_directory())?;
+        self.rescan_credentials(&destination, context);
+        Ok(())
+    }
+
+    /// Reloads credentials from disk and selects the one stored at `selected`.
+    /// The scan looks up passwords, and `secret-tool` can wait on a keyring
+    /// prompt, so it runs in the background.
+    fn rescan_credentials(&mut self, selected: &Path, context: &mut Context<Self>) {
+        self.credential_scan_generation += 1;
+        let generation = self.credential_scan_generation;
+        let selected = selected.to_path_buf();
+        context
+            .spawn(
+                async move |this: WeakEntity<Self>, context: &mut AsyncApp| {
+                    let credentials = context
+                        .background_executor()
+                        .spawn(async move { scan_credentials(&credentials_directory()) })
+                        .await;
+                    if let Err(error) = this.update(context, |this, context| {
+                        if this.credential_scan_generation == generation {
+                            this.apply_scanned_credentials(credentials, &selected, context);
+                        }
+                    }) {
+                        log::warn!("[credentials] failed to update state after rescan: {error}");
+                    }
+                },
+            )
+            .detach();
+    }
+
+    fn apply_scanned_credentials(
+        &mut self,
+        credentials: Vec<StoredCredential>,
+        selected: &Path,
+        context: &mut Context<Self>,
+    ) {
+        let saved_state = AppState::load();
+        self.stored_credentials = credentials;
+        crate::app_state::apply_saved_order(
+            &mut self.stored_credentials,
+            &saved_state.credential_order,
+        );
+        let found_index = self
+            .stored_credentials
+            .iter()
+            .position(|entry| entry.path == selected);
+        if let Some(selected_index) = found_index {
+            self.select_credential(selected_index, context);
+        }
+        self.sync_credential_focus_handles(context);
+        self.save_app_state();
+        context.notify();
+    }
+
+    /// The vault holds passwords but has not been unlocked in this session.
+    fn vault_locked(&self) -> bool {
+        !secret_store::vault_unlocked()
+            && (secret_store::uses_vault()
+                || self.stored_credentials.iter().any(|stored| {
+                    secret_store::is_vault_reference(&stored.credential.password_ref)
+                }))
+    }
+
+    fn unlock_vault(&mut self, context: &mut Context<Self>) {
+        let passphrase = self.vault_passphrase_input.read(context).text();
+        let confirmation = self.vault_confirmation_input.read(context).text();
+        if let Err(error) = secret_store::unlock_vault(&passphrase, &confirmation) {
+            log::warn!("[credentials] vault unlock failed: {error}");
+            self.status_detail = error;
+            context.notify();
+            return;
+        }
+        self.set_input(&self.vault_passphrase_input.clone(), "", context);
+        self.set_input(&self.vault_confirmation_input.clone(), "", context);
+        self.save_draft_credential(context);
+
+        let selected = self
+            .selected_credential
+            .and_then(|index| self.stored_credentials.get(index))
+            .map(|stored| stored.path.clone())
+            .unwrap_or_default();
+        self.rescan_credentials(&selected, context);
+        self.status_detail = "Credential vault unlocked".into();
+    }
+
     fn add_credential(
         &mut self,
         _: &AddCredential,
@@ -944,6 +1358,134 @@ impl TrustTunnelApp {
         context.notify();
     }
 
+    #[cfg(target_os = "linux")]
+    fn control_state(&self) -> Value {
+        let credential = self
+            .selected_credential
+            .and_then(|index| self.stored_credentials.get(index))
+            .map(|stored| stored.name.clone());
+        let mut state = json!({
+            "state": self.connection_state.name(),
+            "mode": self.tunnel_mode.name(),
+            "credential": credential,
+            "detail": self.status_detail,
+        });
+        match self.connection_state {
+            ConnectionState::Reconnecting(attempt) => {
+                state["attempt"] = json!(attempt);
+                state["max_attempts"] = json!(self.reconnect_policy.max_attempts);
+            }
+            ConnectionState::Error(ref message) => state["error"] = json!(message),
+            _ => {}
+        }
+        state
+    }
+
+    /// Emits a `state` event whenever the state seen by the control socket changes.
+    #[cfg(target_os = "linux")]
+    fn publish_control_state(&mut self) {
+        let state = self.control_state();
+        if self.published_state.as_ref() == Some(&state) {
+            return;
+        }
+        let mut event = state.clone();
+        event["event"] = json!("state");
+        self.control_events.emit(event);
+        self.published_state = Some(state);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn handle_control_request(
+        &mut self,
+        request: ControlRequest,
+        window: &mut Window,
+        context: &mut Context<Self>,
+    ) {
+        let result = match request.command {
+            ControlCommand::GetState => Ok(self.control_state()),
+            ControlCommand::ListCredentials => Ok(Value::Array(
+                self.stored_credentials
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, stored)| !stored.draft)
+                    .map(|(index, stored)| {
+                        json!({
+                            "name": stored.name,
+                            "hostname": stored.credential.hostname,
+                            "selected": self.selected_credential == Some(index),
+                        })
+                    })
+                    .collect(),
+            )),
+            ControlCommand::Connect { ref name, ref mode } => {
+                self.control_connect(name, mode.as_deref(), context)
+            }
+            ControlCommand::Disconnect => {
+                if self.connection_state.is_active() {
+                    self.stop_connection(context);
+                    Ok(self.control_state())
+                } else {
+                    Err("not connected".into())
+                }
+            }
+            ControlCommand::TailLog { lines } => {
+                let tail: Vec<String> = self
+                    .process_log
+                    .lock()
+                    .map(|locked_log| {
+                        let start = locked_log.lines.len().saturating_sub(lines);
+                        locked_log.lines[start..].to_vec()
+                    })
+                    .unwrap_or_default();
+                Ok(json!(tail))
+            }
+            ControlCommand::Import { ref path } => {
+                window.activate_window();
+                self.import_credential_path(path, context)
+                    .map(|_| self.control_state())
+            }
+            ControlCommand::Focus => {
+                window.activate_window();
+                Ok(self.control_state())
+            }
+            ControlCommand::Subscribe => Err("subscribe is handled by the socket".into()),
+        };
+        request.respond(result);
+        context.notify();
+    }
+
+    #[cfg(target_os = "linux")]
+    fn control_connect(
+        &mut self,
+        name: &str,
+        mode: Option<&str>,
+        context: &mut Context<Self>,
+    ) -> Result<Value, String> {
+        if self.is_locked() {
+            return Err(format!(
+                "already {}, disconnect first",
+                self.connection_state.name()
+            ));
+        }
+        let index = find_credential_index(&self.stored_credentials, name)
+            .ok_or_else(|| format!("no stored credential named '{name}'"))?;
+        if let Some(mode) = mode {
+            let mode = TunnelMode::from_name(mode).ok_or_else(|| {
+                format!("unknown mode '{mode}' (expected tun, proxy or system-proxy)")
+            })?;
+            self.set_tunnel_mode(mode, context);
+        }
+
+        self.select_credential(index, context);
+        self.start_connection(context);
+        match self.connection_state {
+            ConnectionState::Error(ref message) => {
+                Err(format!("{message}: {}", self.status_detail))
+            }
+            _ => Ok(self.control_state()),
+        }
+    }
+
     fn select_credential(&mut self, index: usize, context: &mut Context<Self>) {
         if index >= self.stored_credentials.len() {
             return;
@@ -956,6 +1498,8 @@ impl TrustTunnelApp {
     }
 
     fn load_credential(&mut self, credential: &CredentialFile, context: &mut Context<Self>) {
+        self.field_errors.clear();
+        self.offered_client_download = None;
         self.set_input(&self.hostname_input.clone(), &credential.hostname, context);
         self.set_input(
             &self.addresses_input.clone(),
@@ -969,6 +1513,26 @@ impl TrustTunnelApp {
             &credential.dns_upstreams.join(", "),
             context,
         );
+        self.set_input(
+            &self.included_routes_input.clone(),
+            &credential.included_routes.join(", "),
+            context,
+        );
+        self.set_input(
+            &self.excluded_routes_input.clone(),
+            &credential.excluded_routes.join(", "),
+            context,
+        );
+        self.set_input(
+            &self.exclusions_input.clone(),
+            &credential.exclusions.join(", "),
+            context,
+        );
+        self.set_input(
+            &self.client_version_input.clone(),
+            &credential.client_version,
+            context,
+        );
         let certificate = credential.certificate.trim().to_string();
         self.certificate_input
             .update(context, |area, _| area.set_content(&certificate));
@@ -983,6 +1547,11 @@ impl TrustTunnelApp {
             credential.upstream_protocol.clone()
         };
         self.upstream_fallback_protocol = credential.upstream_fallback_protocol.clone();
+        self.vpn_mode = if credential.vpn_mode.is_empty() {
+            "general".into()
+        } else {
+            credential.vpn_mode.clone()
+        };
         context.notify();
     }
 
@@ -1022,7 +1591,7 @@ impl TrustTunnelApp {
     }
 
     fn toggle_anti_dpi(&mut self, context: &mut Context<Self>) {
-        if !self.is_locked() {
+        if !self.is_locked() && self.client_supports(client_capabilities::ANTI_DPI, context) {
             self.anti_dpi = !self.anti_dpi;
             context.notify();
         }
@@ -1036,7 +1605,9 @@ impl TrustTunnelApp {
     }
 
     fn toggle_post_quantum_group_enabled(&mut self, context: &mut Context<Self>) {
-        if !self.is_locked() {
+        if !self.is_locked()
+            && self.client_supports(client_capabilities::POST_QUANTUM_GROUP, context)
+        {
             self.post_quantum_group_enabled = !self.post_quantum_group_enabled;
             context.notify();
         }
@@ -1050,6 +1621,22 @@ impl TrustTunnelApp {
         }
     }
 
+    fn toggle_proxy_authentication(&mut self, context: &mut Context<Self>) {
+        if !self.is_locked() {
+            self.proxy_authentication = !self.proxy_authentication;
+            self.save_app_state();
+            context.notify();
+        }
+    }
+
+    fn toggle_auto_reconnect(&mut self, context: &mut Context<Self>) {
+        if !self.is_locked() {
+            self.reconnect_policy.enabled = !self.reconnect_policy.enabled;
+            self.save_app_state();
+            context.notify();
+        }
+    }
+
     fn set_upstream_protocol(&mut self, value: &str, context: &mut Context<Self>) {
         if !self.is_locked() {
             self.upstream_protocol = value.into();
@@ -1061,12 +1648,21 @@ impl TrustTunnelApp {
     }
 
     fn set_fallback_protocol(&mut self, value: &str, context: &mut Context<Self>) {
-        if !self.is_locked() {
+        if !self.is_locked()
+            && self.client_supports(client_capabilities::UPSTREAM_FALLBACK_PROTOCOL, context)
+        {
             self.upstream_fallback_protocol = value.into();
             context.notify();
         }
     }
 
+    fn set_vpn_mode(&mut self, value: &str, context: &mut Context<Self>) {
+        if !self.is_locked() {
+            self.vpn_mode = value.into();
+            context.notify();
+        }
+    }
+
     fn set_tunnel_mode(&mut self, mode: TunnelMode, context: &mut Context<Self>) {
         if !self.is_locked() {
             self.tunnel_mode = mode;
@@ -1080,6 +1676,9 @@ impl TrustTunnelApp {
         state.set_credential_order(&self.stored_credentials);
         state.set_tunnel_mode(self.tunnel_mode);
         state.set_dns_enabled(self.dns_enabled);
+        state.set_proxy_listen_address(&self.proxy_listen_address);
+        state.set_proxy_authentication(self.proxy_authentication);
+        state.set_reconnect_policy(self.reconnect_policy);
         state.set_selected_credential(
             self.selected_credential
                 .and_then(|index| self.stored_credentials.get(index)),
@@ -1088,6 +1687,9 @@ impl TrustTunnelApp {
             state.set_selected_client_version(
                 client_manager_state_guard.selected_version.as_deref(),
             );
+            state.set_release_source(&client_manager_state_guard.release_source);
+            state.set_prune_after_upgrade(client_manager_state_guard.prune_after_upgrade);
+            state.set_keep_client_versions(client_manager_state_guard.keep_versions);
         }
         state.save();
     }
@@ -1125,15 +1727,59 @@ impl TrustTunnelApp {
         context.notify();
     }
 
+    fn import_client(&mut self, context: &mut Context<Self>) {
+        if self.is_locked() {
+            return;
+        }
+
+        let receiver = context.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        });
+
+        let client_manager_state = self.client_manager_state.clone();
+        context
+            .spawn(
+                async move |_this: WeakEntity<Self>, _context: &mut AsyncApp| {
+                    let path = match receiver.await {
+                        Ok(Ok(Some(paths))) => match paths.into_iter().next() {
+                            Some(path) => path,
+                            None => return,
+                        },
+                        Ok(Ok(None)) => return,
+                        Ok(Err(error)) => {
+                            log::warn!("[client_manager] file dialog error: {error}");
+                            return;
+                        }
+                        Err(_) => return,
+                    };
+
+                    client_manager::start_local_install(client_manager_state, path);
+                },
+            )
+            .detach();
+    }
+
+    fn cancel_client_download(&mut self, version: String, context: &mut Context<Self>) {
+        client_manager::cancel_download(&self.client_manager_state, &version);
+        context.notify();
+    }
+
     fn download_selected_client_version(&mut self, context: &mut Context<Self>) {
         let release = {
             let client_manager_state_guard = self.client_manager_state.lock().unwrap();
             let Some(version) = client_manager_state_guard.selected_version.as_ref() else {
                 return;
             };
-            if client_manager_state_guard.installed.contains(version)
-                || client_manager_state_guard.is_downloading(version)
-            {
+            if client_manager_state_guard.is_downloading(version) {
+                let version = version.clone();
+                drop(client_manager_state_guard);
+                self.cancel_client_download(version, context);
+                return;
+            }
+            if client_manager_state_guard.installed.contains(version) {
                 return;
             }
             client_manager_state_guard
@@ -1174,15 +1820,154 @@ impl TrustTunnelApp {
         if let Err(error) = client_manager::remove_client(&version) {
             log::error!("[client_manager] failed to remove {version}: {error}");
         }
+        client_manager::rescan_installed(&self.client_manager_state);
+        self.update_binary_from_client_manager();
+        self.save_app_state();
+        context.notify();
+    }
+
+    /// Saves a client selection made by a background download or upgrade.
+    fn persist_client_selection(&mut self) {
+        let changed = self
+            .client_manager_state
+            .lock()
+            .map(|mut client_manager_state_guard| {
+                std::mem::take(&mut client_manager_state_guard.selection_changed)
+            })
+            .unwrap_or(false);
+        if changed {
+            self.update_binary_from_client_manager();
+            self.save_app_state();
+        }
+    }
+
+    /// Tells the client manager which binary must survive version cleanup.
+    fn publish_running_binary(&self) {
+        let running_binary = self
+            .child_process
+            .is_some()
+            .then(|| PathBuf::from(&self.session_binary_path));
+        if let Ok(mut client_manager_state_guard) = self.client_manager_state.lock()
+            && client_manager_state_guard.running_binary != running_binary
         {
-            let mut client_manager_state_guard = self.client_manager_state.lock().unwrap();
-            client_manager_state_guard.installed = client_manager::scan_installed_clients();
+            client_manager_state_guard.running_binary = running_binary;
         }
-        self.update_binary_from_client_manager();
+    }
+
+    fn upgrade_client(&mut self, context: &mut Context<Self>) {
+        if self.is_locked() {
+            return;
+        }
+        let release = {
+            let client_manager_state_guard = self.client_manager_state.lock().unwrap();
+            if client_manager_state_guard.pending_upgrade.is_some() {
+                return;
+            }
+            client_manager_state_guard.available_update().cloned()
+        };
+        if let Some(release) = release {
+            client_manager::start_upgrade(self.client_manager_state.clone(), release);
+            context.notify();
+        }
+    }
+
+    fn toggle_prerelease_channel(&mut self, context: &mut Context<Self>) {
+        let channel = match self
+            .client_manager_state
+            .lock()
+            .map(|client_manager_state_guard| client_manager_state_guard.release_source.channel)
+        {
+            Ok(ReleaseChannel::Stable) => ReleaseChannel::Prerelease,
+            Ok(ReleaseChannel::Prerelease) => ReleaseChannel::Stable,
+            Err(_) => return,
+        };
+        client_manager::set_release_channel(self.client_manager_state.clone(), channel);
+        self.save_app_state();
+        context.notify();
+    }
+
+    fn apply_release_source(&mut self, context: &mut Context<Self>) {
+        let releases_url = self
+            .release_url_input
+            .read(context)
+            .text()
+            .trim()
+            .to_string();
+        let github_token = self
+            .github_token_input
+            .read(context)
+            .text()
+            .trim()
+            .to_string();
+        let is_http = releases_url.starts_with("http://");
+        if !releases_url.is_empty() && !is_http && !releases_url.starts_with("https://") {
+            self.release_source_error = Some(format!(
+                "'{releases_url}' is not an http:// or https:// URL"
+            ));
+            context.notify();
+            return;
+        }
+        self.release_source_error = (is_http && !github_token.is_empty())
+            .then(|| "The token is only sent over https://, not to this URL".to_string());
+
+        client_manager::set_release_source(
+            self.client_manager_state.clone(),
+            (!releases_url.is_empty()).then_some(releases_url),
+            (!github_token.is_empty()).then_some(github_token),
+        );
+        self.save_app_state();
+        context.notify();
+    }
+
+    fn toggle_prune_after_upgrade(&mut self, context: &mut Context<Self>) {
+        if let Ok(mut client_manager_state_guard) = self.client_manager_state.lock() {
+            client_manager_state_guard.prune_after_upgrade =
+                !client_manager_state_guard.prune_after_upgrade;
+        }
+        self.save_app_state();
+        context.notify();
+    }
+
+    fn set_keep_client_versions(&mut self, keep: Option<usize>, context: &mut Context<Self>) {
+        if let Ok(mut client_manager_state_guard) = self.client_manager_state.lock() {
+            if client_manager_state_guard.keep_versions == keep {
+                return;
+            }
+            client_manager_state_guard.keep_versions = keep;
+        }
+        client_manager::start_retention(self.client_manager_state.clone());
         self.save_app_state();
         context.notify();
     }
 
+    /// The binary a connect would spawn: the installed pinned version when
+    /// the credential pins one, the Client tab selection otherwise.
+    fn prospective_binary(&self, context: &App) -> Option<PathBuf> {
+        let pinned_version = self.client_version_input.read(context).text();
+        let pinned_version = pinned_version.trim();
+        if pinned_version.is_empty() {
+            return self.binary_found.then(|| PathBuf::from(&self.binary_path));
+        }
+        match self
+            .client_manager_state
+            .lock()
+            .ok()?
+            .pinned_client(pinned_version)
+        {
+            PinnedClient::Installed(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Whether the client that would be spawned accepts `key`. Assumed until
+    /// its probe finishes or while there is no binary to probe.
+    fn client_supports(&self, key: &str, context: &App) -> bool {
+        self.prospective_binary(context).is_none_or(|binary| {
+            client_manager::capabilities_for(&self.client_manager_state, &binary)
+                .is_none_or(|capabilities| capabilities.supports(key))
+        })
+    }
+
     fn update_binary_from_client_manager(&mut self) {
         let client_manager_state_guard = self.client_manager_state.lock().unwrap();
         if let Some(managed_path) = client_manager_state_guard.selected_binary_path() {
@@ -1256,6 +2041,20 @@ impl TrustTunnelApp {
             .map(|segment| segment.trim().to_string())
             .filter(|segment| !segment.is_empty())
             .collect();
+        let included_routes = split_list(&self.included_routes_input.read(context).text());
+        let excluded_routes = split_list(&self.excluded_routes_input.read(context).text());
+        let exclusions = split_list(&self.exclusions_input.read(context).text());
+        let client_version = self
+            .client_version_input
+            .read(context)
+            .text()
+            .trim()
+            .to_string();
+        let password_ref = self
+            .selected_credential
+            .and_then(|index| self.stored_credentials.get(index))
+            .map(|stored| stored.credential.password_ref.clone())
+            .unwrap_or_default();
 
         CredentialFile {
             hostname,
@@ -1263,6 +2062,7 @@ impl TrustTunnelApp {
             has_ipv6: self.has_ipv6,
             username,
             password,
+            password_ref,
             skip_verification: self.skip_verification,
             certificate,
             upstream_protocol: self.upstream_protocol.clone(),
@@ -1271,6 +2071,11 @@ impl TrustTunnelApp {
             killswitch_enabled: self.killswitch_enabled,
             post_quantum_group_enabled: self.post_quantum_group_enabled,
             dns_upstreams,
+            included_routes,
+            excluded_routes,
+            vpn_mode: self.vpn_mode.clone(),
+            exclusions,
+            client_version,
         }
     }
 
@@ -1353,7 +2158,33 @@ impl TrustTunnelApp {
         false
     }
 
+    /// Validates the SOCKS listen address and remembers it for the next start.
+    fn prepare_proxy_listener(
+        &mut self,
+        context: &mut Context<Self>,
+    ) -> Result<ProxyListener, (String, String)> {
+        let text = self.proxy_listen_input.read(context).text();
+        let text = if text.trim().is_empty() {
+            DEFAULT_PROXY_LISTEN_ADDRESS.to_string()
+        } else {
+            text.trim().to_string()
+        };
+        let address = parse_proxy_listen_address(&text)
+            .map_err(|error| ("Invalid SOCKS listen address".to_string(), error))?;
+
+        if self.proxy_listen_address != text {
+            self.proxy_listen_address = text;
+            self.save_app_state();
+        }
+
+        ProxyListener::prepare(address, self.proxy_authentication)
+    }
+
     fn connect(&mut self, _: &Connect, _window: &mut Window, context: &mut Context<Self>) {
+        self.start_connection(context);
+    }
+
+    fn start_connection(&mut self, context: &mut Context<Self>) {
         if self.is_locked() {
             return;
         }
@@ -1363,21 +2194,10 @@ impl TrustTunnelApp {
         let mode = self.tunnel_mode;
         log::info!("━━━ CONNECT (mode={}) ━━━", mode.label());
 
-        if !self.binary_found {
-            self.connection_state = ConnectionState::Error("Client binary not found".into());
-            self.status_detail = if cfg!(target_os = "windows") {
-                "Could not find 'trusttunnel_client.exe' in PATH or standard locations.\n\n\
-                 Install the TrustTunnel client:\n  \
-                 https://github.com/TrustTunnel/TrustTunnelClient"
-            } else {
-                "Could not find 'trusttunnel_client' in PATH or standard locations.\n\n\
-                 Install the TrustTunnel client:\n  \
-                 https://github.com/TrustTunnel/TrustTunnelClient"
-            }
-            .to_string();
+        let Some(binary_path) = self.resolve_session_binary(context) else {
             context.notify();
             return;
-        }
+        };
 
         if mode.is_tun() && !self.system_services.check_tun_device() {
             self.connection_state = ConnectionState::Error("TUN device not available".into());
@@ -1425,40 +2245,104 @@ impl TrustTunnelApp {
 
         let credential = self.build_credential_from_fields(context);
 
-        if let Some(error) = credential.validate() {
-            self.connection_state = ConnectionState::Error(error.0.clone());
-            self.status_detail = error.1.clone();
+        self.field_errors = credential.validate_fields();
+        if let Some(error) = self.field_errors.first() {
+            self.connection_state = ConnectionState::Error(error.title.clone());
+            self.status_detail = error.detail.clone();
             context.notify();
             return;
         }
 
+        let proxy_listener = if mode.is_tun() {
+            ProxyListener::default()
+        } else {
+            match self.prepare_proxy_listener(context) {
+                Ok(listener) => listener,
+                Err((message, detail)) => {
+                    self.connection_state = ConnectionState::Error(message);
+                    self.status_detail = detail;
+                    context.notify();
+                    return;
+                }
+            }
+        };
+
         let endpoint = credential.to_endpoint_fields(self.dns_enabled);
-        let mut configuration = VpnConfiguration::new(endpoint, mode);
+        let mut configuration = VpnConfiguration::new(endpoint, mode, &proxy_listener);
+        self.proxy_listener = (!mode.is_tun()).then_some(proxy_listener);
 
-        if mode.is_tun()
-            && let Some(ref mut tun) = configuration.listener.tun
+        configuration.exclude_endpoint_addresses();
+
+        if let Some(capabilities) =
+            client_manager::cached_capabilities(&self.client_manager_state, Path::new(&binary_path))
         {
-            for endpoint_address in &configuration.endpoint.addresses {
-                let host = endpoint_address
-                    .split(':')
-                    .next()
-                    .unwrap_or(endpoint_address);
-                if let Ok(address) = host.parse::<std::net::IpAddr>() {
-                    let route = match address {
-                        std::net::IpAddr::V4(ipv4) => format!("{ipv4}/32"),
-                        std::net::IpAddr::V6(ipv6) => format!("{ipv6}/128"),
-                    };
-                    if !tun.excluded_routes.contains(&route) {
-                        tun.excluded_routes.push(route);
+            self.finish_connection(configuration, mode, binary_path, &capabilities, context);
+            return;
+        }
+
+        // Running `--version` and `--help` can take seconds, so the probe
+        // happens off the UI thread and the connection continues afterwards.
+        if let Ok(mut locked_log) = self.process_log.lock() {
+            locked_log.reset();
+        }
+        self.capability_probe_pending = true;
+        self.connection_state = ConnectionState::Connecting;
+        self.status_detail = "Checking client capabilities…".into();
+        context.notify();
+
+        let client_manager_state = self.client_manager_state.clone();
+        context
+            .spawn(
+                async move |this: WeakEntity<Self>, context: &mut AsyncApp| {
+                    let probe_path = PathBuf::from(&binary_path);
+                    let capabilities = context
+                        .background_executor()
+                        .spawn(async move {
+                            client_manager::probe_capabilities_blocking(
+                                &client_manager_state,
+                                &probe_path,
+                            )
+                        })
+                        .await;
+
+                    if let Err(error) = this.update(context, |this, context| {
+                        if !this.capability_probe_pending {
+                            log::info!("[connect] connection cancelled during capability probe");
+                            return;
+                        }
+                        this.capability_probe_pending = false;
+                        this.finish_connection(
+                            configuration,
+                            mode,
+                            binary_path,
+                            &capabilities,
+                            context,
+                        );
+                    }) {
+                        log::warn!("[connect] failed to continue after capability probe: {error}");
                     }
+                },
+            )
+            .detach();
+    }
+
+    /// Writes the configuration for the probed client and starts it.
+    fn finish_connection(
+        &mut self,
+        configuration: VpnConfiguration,
+        mode: TunnelMode,
+        binary_path: String,
+        capabilities: &client_capabilities::ClientCapabilities,
+        context: &mut Context<Self>,
+    ) {
+        let toml_string = match configuration.to_toml(capabilities) {
+            Ok((value, warnings)) => {
+                for warning in warnings {
+                    log::warn!("[connect] {warning}");
                 }
+                value
             }
-        }
-
-        let toml_string = match toml::to_string_pretty(&configuration) {
-            Ok(value) => value,
-            Err(error) => {
-                let message = format!("Configuration serialization error: {error}");
+            Err(message) => {
                 self.connection_state = ConnectionState::Error(message.clone());
                 self.status_detail = message;
                 context.notify();
@@ -1471,14 +2355,22 @@ impl TrustTunnelApp {
             redact_password_in_toml(&toml_string),
         );
 
-        if let Err(error) = std::fs::write(&self.configuration_path, &toml_string) {
-            let message = format!("Failed to write config: {error}");
+        if let Err(message) = write_private_file(&self.configuration_path, &toml_string) {
             self.connection_state = ConnectionState::Error(message.clone());
             self.status_detail = message;
             context.notify();
             return;
         }
 
+        self.reconnect_attempt = 0;
+        self.reconnect_due = None;
+        self.session_binary_path = binary_path;
+        self.spawn_client_process(mode, context);
+        context.notify();
+    }
+
+    /// Starts the client on the already written configuration file.
+    fn spawn_client_process(&mut self, mode: TunnelMode, context: &mut Context<Self>) -> bool {
         self.log_file = self.create_session_log_file();
         if let Ok(mut locked_log) = self.process_log.lock() {
             locked_log.reset();
@@ -1487,7 +2379,7 @@ impl TrustTunnelApp {
         context.notify();
 
         let spawn_result = self.system_services.spawn_client(
-            &self.binary_path,
+            &self.session_binary_path,
             &self.configuration_path,
             mode.is_tun(),
         );
@@ -1505,7 +2397,9 @@ impl TrustTunnelApp {
                 );
                 self.start_log_reader(&mut child);
                 self.child_process = Some(child);
+                self.publish_running_binary();
                 self.status_detail = String::new();
+                true
             }
             Err(error) => {
                 self.connection_state = ConnectionState::Error("Failed to start client".into());
@@ -1514,16 +2408,23 @@ impl TrustTunnelApp {
                      Install the TrustTunnel client:\n  \
                      https://github.com/TrustTunnel/TrustTunnelClient"
                 );
+                false
             }
         }
-        context.notify();
     }
 
     fn disconnect(&mut self, _: &Disconnect, _window: &mut Window, context: &mut Context<Self>) {
+        self.stop_connection(context);
+    }
+
+    fn stop_connection(&mut self, context: &mut Context<Self>) {
         if matches!(self.connection_state, ConnectionState::Disconnecting) {
             return;
         }
         log::info!("━━━ DISCONNECT ━━━");
+        self.capability_probe_pending = false;
+        self.reconnect_attempt = 0;
+        self.reconnect_due = None;
 
         if !self.proxy_overrides.is_empty() {
             log::info!("[disconnect] restoring system proxy");
@@ -1558,22 +2459,126 @@ impl TrustTunnelApp {
         }
     }
 
+    /// The client to run: the version the credential is pinned to, or the
+    /// Client tab selection. Sets the error state when it is unavailable. A
+    /// pinned release that is published but not installed is offered first
+    /// and downloaded on the next connect attempt.
+    fn resolve_session_binary(&mut self, context: &App) -> Option<String> {
+        let pinned_version = self
+            .client_version_input
+            .read(context)
+            .text()
+            .trim()
+            .to_string();
+
+        if pinned_version.is_empty() {
+            if self.binary_found {
+                return Some(self.binary_path.clone());
+            }
+            self.connection_state = ConnectionState::Error("Client binary not found".into());
+            self.status_detail = if cfg!(target_os = "windows") {
+                "Could not find 'trusttunnel_client.exe' in PATH or standard locations.\n\n\
+                 Install the TrustTunnel client:\n  \
+                 https://github.com/TrustTunnel/TrustTunnelClient"
+            } else {
+                "Could not find 'trusttunnel_client' in PATH or standard locations.\n\n\
+                 Install the TrustTunnel client:\n  \
+                 https://github.com/TrustTunnel/TrustTunnelClient"
+            }
+            .to_string();
+            return None;
+        }
+
+        let pinned = self
+            .client_manager_state
+            .lock()
+            .unwrap()
+            .pinned_client(&pinned_version);
+        self.offered_client_download = None;
+        match pinned {
+            PinnedClient::Installed(path) => {
+                log::info!("[connect] using pinned client {pinned_version}");
+                return Some(path.to_string_lossy().to_string());
+            }
+            PinnedClient::Downloading(tag) => {
+                self.connection_state =
+                    ConnectionState::Error(format!("Downloading client {tag}…"));
+                self.status_detail = format!(
+                    "This credential needs client {tag}. Connect again once the download in the Client tab finishes."
+                );
+            }
+            PinnedClient::Available(release) => {
+                self.connection_state =
+                    ConnectionState::Error(format!("Client {} not installed", release.tag));
+                self.status_detail = format!(
+                    "This credential is pinned to client {}, which is not installed ({} download).",
+                    release.tag,
+                    client_manager::format_bytes(release.asset_size)
+                );
+                self.offered_client_download = Some(release.tag);
+            }
+            PinnedClient::Unknown => {
+                self.connection_state =
+                    ConnectionState::Error(format!("Client {pinned_version} unavailable"));
+                self.status_detail = format!(
+                    "This credential is pinned to client {pinned_version}, which is neither installed \
+                     nor published by the release source. Import it in the Client tab or clear \
+                     the Client Version field."
+                );
+            }
+        }
+        None
+    }
+
+    fn download_offered_client(&mut self, context: &mut Context<Self>) {
+        let Some(tag) = self.offered_client_download.take() else {
+            return;
+        };
+        let pinned = self
+            .client_manager_state
+            .lock()
+            .unwrap()
+            .pinned_client(&tag);
+        if let PinnedClient::Available(release) = pinned {
+            log::info!("[connect] downloading pinned client {tag}");
+            self.connection_state = ConnectionState::Disconnected;
+            self.status_detail = format!(
+                "Downloading client {tag}, progress is shown in the Client tab. Connect once it finishes."
+            );
+            client_manager::start_download(self.client_manager_state.clone(), release);
+        }
+        context.notify();
+    }
+
+    fn field_error(&self, field: CredentialField) -> Option<&str> {
+        self.field_errors
+            .iter()
+            .find(|error| error.field == field)
+            .map(|error| error.detail.as_str())
+    }
+
     fn on_connect_click(
         &mut self,
         _: &MouseUpEvent,
         window: &mut Window,
         context: &mut Context<Self>,
     ) {
-        if self.connection_state.is_busy() {
-            return;
-        }
-        if self.connection_state.is_connected() {
+        if self.connection_state.is_connected() || self.reconnect_in_progress() {
             self.disconnect(&Disconnect, window, context);
-        } else {
+        } else if !self.connection_state.is_busy() {
             self.connect(&Connect, window, context);
         }
     }
 
+    /// Waiting for or running a reconnect attempt; the button cancels both.
+    fn reconnect_in_progress(&self) -> bool {
+        match self.connection_state {
+            ConnectionState::Reconnecting(_) => true,
+            ConnectionState::Connecting => self.reconnect_attempt > 0,
+            _ => false,
+        }
+    }
+
     fn activate(&mut self, _: &Activate, window: &mut Window, context: &mut Context<Self>) {
         if self.active_tab == ActiveTab::Client {
             if let Some(index) = self
@@ -1582,6 +2587,22 @@ impl TrustTunnelApp {
                 .position(|handle| handle.is_focused(window))
             {
                 self.activate_client_version_button(index, context);
+            } else if self.client_upgrade_focus_handle.is_focused(window) {
+                self.upgrade_client(context);
+            } else if self.client_prune_focus_handle.is_focused(window) {
+                self.toggle_prune_after_upgrade(context);
+            } else if self.client_channel_focus_handle.is_focused(window) {
+                self.toggle_prerelease_channel(context);
+            } else if let Some(index) = self
+                .client_keep_focus_handles
+                .iter()
+                .position(|handle| handle.is_focused(window))
+            {
+                self.set_keep_client_versions(KEEP_CLIENT_VERSION_OPTIONS[index], context);
+            } else if self.client_release_source_focus_handle.is_focused(window) {
+                self.apply_release_source(context);
+            } else if self.client_import_focus_handle.is_focused(window) {
+                self.import_client(context);
             } else if self.client_download_focus_handle.is_focused(window) {
                 self.download_selected_client_version(context);
             } else if self.client_remove_focus_handle.is_focused(window) {
@@ -1629,6 +2650,10 @@ impl TrustTunnelApp {
             self.set_fallback_protocol("http2", context);
         } else if self.fallback_http3_focus_handle.is_focused(window) {
             self.set_fallback_protocol("http3", context);
+        } else if self.vpn_mode_general_focus_handle.is_focused(window) {
+            self.set_vpn_mode("general", context);
+        } else if self.vpn_mode_selective_focus_handle.is_focused(window) {
+            self.set_vpn_mode("selective", context);
         } else if self.mode_tun_focus_handle.is_focused(window) {
             self.set_tunnel_mode(TunnelMode::Tun, context);
         } else if self.mode_system_proxy_focus_handle.is_focused(window) {
@@ -1637,16 +2662,20 @@ impl TrustTunnelApp {
             self.set_tunnel_mode(TunnelMode::Proxy, context);
         } else if self.dns_enabled_focus_handle.is_focused(window) {
             self.toggle_dns_enabled(context);
+        } else if self.proxy_authentication_focus_handle.is_focused(window) {
+            self.toggle_proxy_authentication(context);
+        } else if self.auto_reconnect_focus_handle.is_focused(window) {
+            self.toggle_auto_reconnect(context);
         } else if self.killswitch_focus_handle.is_focused(window) {
             self.toggle_killswitch_enabled(context);
         } else if self.post_quantum_focus_handle.is_focused(window) {
             self.toggle_post_quantum_group_enabled(context);
-        } else if self.connect_button_focus_handle.is_focused(window)
-            && !self.connection_state.is_busy()
-        {
-            if self.connection_state.is_connected() {
+        } else if self.offered_download_focus_handle.is_focused(window) {
+            self.download_offered_client(context);
+        } else if self.connect_button_focus_handle.is_focused(window) {
+            if self.connection_state.is_connected() || self.reconnect_in_progress() {
                 self.disconnect(&Disconnect, window, context);
-            } else {
+            } else if !self.connection_state.is_busy() {
                 self.connect(&Connect, window, context);
             }
         }
@@ -1654,13 +2683,42 @@ impl TrustTunnelApp {
 
     fn focusable_entries(&self, context: &App) -> Vec<(FocusHandle, Option<ScrollAnchor>)> {
         if self.active_tab == ActiveTab::Client {
-            let mut entries: Vec<(FocusHandle, Option<ScrollAnchor>)> = self
-                .client_version_focus_handles
-                .iter()
-                .map(|handle| (handle.clone(), None))
-                .collect();
+            let mut entries: Vec<(FocusHandle, Option<ScrollAnchor>)> = Vec::new();
             let locked = self.is_locked();
+            if let Ok(client_manager_state_guard) = self.client_manager_state.lock()
+                && client_manager_state_guard.available_update().is_some()
+            {
+                if !locked && client_manager_state_guard.pending_upgrade.is_none() {
+                    entries.push((self.client_upgrade_focus_handle.clone(), None));
+                }
+                entries.push((self.client_prune_focus_handle.clone(), None));
+            }
+            entries.push((self.client_channel_focus_handle.clone(), None));
+            entries.extend(
+                self.client_keep_focus_handles
+                    .iter()
+                    .map(|handle| (handle.clone(), None)),
+            );
+            entries.extend([
+                (
+                    self.release_url_input.read(context).focus_handle.clone(),
+                    None,
+                ),
+                (
+                    self.github_token_input.read(context).focus_handle.clone(),
+                    None,
+                ),
+                (self.client_release_source_focus_handle.clone(), None),
+            ]);
+            entries.extend(
+                self.client_version_focus_handles
+                    .iter()
+                    .map(|handle| (handle.clone(), None)),
+            );
             if let Ok(client_manager_state_guard) = self.client_manager_state.lock() {
+                if !locked && !client_manager_state_guard.importing {
+                    entries.push((self.client_import_focus_handle.clone(), None));
+                }
                 let selected = client_manager_state_guard.selected_version.as_deref();
                 let selected_is_installed = selected.is_some_and(|v| {
                     client_manager_state_guard
@@ -1680,7 +2738,7 @@ impl TrustTunnelApp {
                     && has_release
                     && !selected_is_downloading;
                 let can_remove = selected.is_some() && selected_is_installed && !locked;
-                if can_download {
+                if can_download || selected_is_downloading {
                     entries.push((self.client_download_focus_handle.clone(), None));
                 }
                 if can_remove {
@@ -1701,6 +2759,28 @@ impl TrustTunnelApp {
             (self.add_focus_handle.clone(), Some(anchors[9].clone())),
             (self.remove_focus_handle.clone(), Some(anchors[9].clone())),
         ]);
+        if self.vault_locked() {
+            entries.push((
+                self.vault_passphrase_input
+                    .read(context)
+                    .focus_handle
+                    .clone(),
+                Some(anchors[9].clone()),
+            ));
+            if !secret_store::vault_exists() {
+                entries.push((
+                    self.vault_confirmation_input
+                        .read(context)
+                        .focus_handle
+                        .clone(),
+                    Some(anchors[9].clone()),
+                ));
+            }
+            entries.push((
+                self.vault_unlock_focus_handle.clone(),
+                Some(anchors[9].clone()),
+            ));
+        }
         entries.extend([
             (
                 self.hostname_input.read(context).focus_handle.clone(),
@@ -1726,6 +2806,20 @@ impl TrustTunnelApp {
                 self.dns_upstreams_input.read(context).focus_handle.clone(),
                 Some(anchors[5].clone()),
             ),
+            (
+                self.included_routes_input
+                    .read(context)
+                    .focus_handle
+                    .clone(),
+                Some(anchors[10].clone()),
+            ),
+            (
+                self.excluded_routes_input
+                    .read(context)
+                    .focus_handle
+                    .clone(),
+                Some(anchors[11].clone()),
+            ),
             (self.ipv6_focus_handle.clone(), Some(anchors[6].clone())),
             (
                 self.skip_verification_focus_handle.clone(),
@@ -1757,16 +2851,49 @@ impl TrustTunnelApp {
                 Some(anchors[8].clone()),
             ));
         }
+        entries.extend([
+            (
+                self.vpn_mode_general_focus_handle.clone(),
+                Some(anchors[12].clone()),
+            ),
+            (
+                self.vpn_mode_selective_focus_handle.clone(),
+                Some(anchors[12].clone()),
+            ),
+            (
+                self.exclusions_input.read(context).focus_handle.clone(),
+                Some(anchors[13].clone()),
+            ),
+            (
+                self.client_version_input.read(context).focus_handle.clone(),
+                Some(anchors[14].clone()),
+            ),
+        ]);
         entries.extend([
             (self.mode_tun_focus_handle.clone(), None),
             (self.mode_system_proxy_focus_handle.clone(), None),
             (self.mode_proxy_focus_handle.clone(), None),
+        ]);
+        if !self.tunnel_mode.is_tun() {
+            entries.extend([
+                (
+                    self.proxy_listen_input.read(context).focus_handle.clone(),
+                    None,
+                ),
+                (self.proxy_authentication_focus_handle.clone(), None),
+            ]);
+        }
+        entries.extend([
             (self.dns_enabled_focus_handle.clone(), None),
+            (self.auto_reconnect_focus_handle.clone(), None),
             (self.killswitch_focus_handle.clone(), None),
             (self.post_quantum_focus_handle.clone(), None),
             (self.connect_button_focus_handle.clone(), None),
-            (self.log_panel.focus_handle(context), None),
         ]);
+        if self.offered_client_download.is_some() {
+            entries.push((self.offered_download_focus_handle.clone(), None));
+        }
+        entries.push((self.log_panel.focus_handle(context), None));
         entries
     }
 
@@ -1879,6 +3006,13 @@ impl Focusable for TrustTunnelApp {
     }
 }
 
+fn split_list(text: &str) -> Vec<String> {
+    text.split([',', ' '])
+        .map(|segment| segment.trim().to_string())
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
 fn resize_edge(position: Point<Pixels>, inset: Pixels, size: Size<Pixels>) -> Option<ResizeEdge> {
     let edge = if position.y < inset && position.x < inset {
         ResizeEdge::TopLeft
@@ -1917,6 +3051,12 @@ impl Render for TrustTunnelApp {
             self.poll_process_state(context);
         }
 
+        #[cfg(target_os = "linux")]
+        self.publish_control_state();
+
+        self.persist_client_selection();
+        self.publish_running_binary();
+
         if self.active_tab == ActiveTab::Client {
             context.notify();
         }
@@ -1949,14 +3089,50 @@ impl Render for TrustTunnelApp {
             .update(context, |input, _| input.disabled = locked);
         self.dns_upstreams_input
             .update(context, |input, _| input.disabled = locked);
+        self.included_routes_input
+            .update(context, |input, _| input.disabled = locked);
+        self.excluded_routes_input
+            .update(context, |input, _| input.disabled = locked);
+        self.exclusions_input
+            .update(context, |input, _| input.disabled = locked);
+        self.client_version_input
+            .update(context, |input, _| input.disabled = locked);
+
+        // Once a connect attempt has flagged fields, keep the messages in
+        // step with what is being typed so fixed fields clear themselves.
+        if !self.field_errors.is_empty() {
+            self.field_errors = self.build_credential_from_fields(context).validate_fields();
+        }
+        self.proxy_listen_input
+            .update(context, |input, _| input.disabled = locked);
+        self.vault_passphrase_input
+            .update(context, |input, _| input.disabled = locked);
+        self.vault_confirmation_input
+            .update(context, |input, _| input.disabled = locked);
         self.certificate_input
             .update(context, |area, _| area.disabled = locked);
 
+        let reconnect_label = format!(
+            "Reconnecting… {}/{}",
+            self.reconnect_attempt, self.reconnect_policy.max_attempts,
+        );
         let (button_label, button_background, button_hover_background, button_busy) = match &self
             .connection_state
         {
             ConnectionState::Disconnected => ("Connect", BUTTON_FILLED, BUTTON_FILLED_HOVER, false),
+            ConnectionState::Connecting if self.reconnect_attempt > 0 => (
+                reconnect_label.as_str(),
+                COLOR_YELLOW,
+                BUTTON_DANGER_HOVER,
+                false,
+            ),
             ConnectionState::Connecting => ("Connecting…", COLOR_YELLOW, COLOR_YELLOW, true),
+            ConnectionState::Reconnecting(_) => (
+                reconnect_label.as_str(),
+                COLOR_YELLOW,
+                BUTTON_DANGER_HOVER,
+                false,
+            ),
             ConnectionState::Connected => ("Disconnect", BUTTON_DANGER, BUTTON_DANGER_HOVER, false),
             ConnectionState::Disconnecting => ("Disconnecting…", COLOR_YELLOW, COLOR_YELLOW, true),
             ConnectionState::Error(message) => {
@@ -1966,6 +3142,7 @@ impl Render for TrustTunnelApp {
 
         let upstream = self.upstream_protocol.clone();
         let fallback = self.upstream_fallback_protocol.clone();
+        let vpn_mode = self.vpn_mode.clone();
 
         let decorations = window.window_decorations();
         let resize_border = px(5.0);
@@ -2037,9 +3214,10 @@ impl Render for TrustTunnelApp {
                                                 .anchor_scroll(Some(
                                                     self.configuration_scroll_anchors[0].clone(),
                                                 ))
-                                                .child(field(
+                                                .child(validated_field(
                                                     "Hostname",
                                                     self.hostname_input.clone(),
+                                                    self.field_error(CredentialField::Hostname),
                                                 )),
                                         )
                                         .child(
@@ -2048,9 +3226,10 @@ impl Render for TrustTunnelApp {
                                                 .anchor_scroll(Some(
                                                     self.configuration_scroll_anchors[1].clone(),
                                                 ))
-                                                .child(field(
+                                                .child(validated_field(
                                                     "Addresses (comma-separated)",
                                                     self.addresses_input.clone(),
+                                                    self.field_error(CredentialField::Addresses),
                                                 )),
                                         )
                                         .child(
@@ -2059,9 +3238,10 @@ impl Render for TrustTunnelApp {
                                                 .anchor_scroll(Some(
                                                     self.configuration_scroll_anchors[2].clone(),
                                                 ))
-                                                .child(field(
+                                                .child(validated_field(
                                                     "Username",
                                                     self.username_input.clone(),
+                                                    self.field_error(CredentialField::Username),
                                                 )),
                                         )
                                         .child(
@@ -2070,9 +3250,10 @@ impl Render for TrustTunnelApp {
                                                 .anchor_scroll(Some(
                                                     self.configuration_scroll_anchors[3].clone(),
                                                 ))
-                                                .child(field(
+                                                .child(validated_field(
                                                     "Password",
                                                     self.password_input.clone(),
+                                                    self.field_error(CredentialField::Password),
                                                 )),
                                         ),
                                 )
@@ -2082,9 +3263,10 @@ impl Render for TrustTunnelApp {
                                         .anchor_scroll(Some(
                                             self.configuration_scroll_anchors[4].clone(),
                                         ))
-                                        .child(field(
+                                        .child(validated_field(
                                             "Certificate (PEM)",
                                             self.certificate_input.clone(),
+                                            self.field_error(CredentialField::Certificate),
                                         )),
                                 )
                                 .child(
@@ -2093,9 +3275,34 @@ impl Render for TrustTunnelApp {
                                         .anchor_scroll(Some(
                                             self.configuration_scroll_anchors[5].clone(),
                                         ))
-                                        .child(field(
+                                        .child(validated_field(
                                             "DNS Upstreams (comma-separated)",
                                             self.dns_upstreams_input.clone(),
+                                            self.field_error(CredentialField::DnsUpstreams),
+                                        )),
+                                )
+                                .child(
+                                    div()
+                                        .id("anchor-included-routes")
+                                        .anchor_scroll(Some(
+                                            self.configuration_scroll_anchors[10].clone(),
+                                        ))
+                                        .child(validated_field(
+                                            "Included Routes (TUN, empty for default)",
+                                            self.included_routes_input.clone(),
+                                            self.field_error(CredentialField::IncludedRoutes),
+                                        )),
+                                )
+                                .child(
+                                    div()
+                                        .id("anchor-excluded-routes")
+                                        .anchor_scroll(Some(
+                                            self.configuration_scroll_anchors[11].clone(),
+                                        ))
+                                        .child(validated_field(
+                                            "Excluded Routes (TUN, empty for default)",
+                                            self.excluded_routes_input.clone(),
+                                            self.field_error(CredentialField::ExcludedRoutes),
                                         )),
                                 )
                                 .child(
@@ -2116,6 +3323,10 @@ impl Render for TrustTunnelApp {
                                             self.render_upstream_selector(
                                                 &upstream, locked, context,
                                             ),
+                                        )
+                                        .when_some(
+                                            self.field_error(CredentialField::UpstreamProtocol),
+                                            |element, error| element.child(field_error(error)),
                                         ),
                                 )
                                 .child(
@@ -2124,11 +3335,32 @@ impl Render for TrustTunnelApp {
                                         .anchor_scroll(Some(
                                             self.configuration_scroll_anchors[8].clone(),
                                         ))
-                                        .child(
-                                            self.render_fallback_selector(
-                                                &fallback, locked, context,
-                                            ),
+                                        .child(self.render_fallback_selector(
+                                            &fallback,
+                                            locked
+                                                || !self.client_supports(
+                                                    client_capabilities::UPSTREAM_FALLBACK_PROTOCOL,
+                                                    context,
+                                                ),
+                                            context,
+                                        ))
+                                        .when_some(
+                                            self.field_error(CredentialField::FallbackProtocol),
+                                            |element, error| element.child(field_error(error)),
                                         ),
+                                )
+                                .child(self.render_vpn_mode_fields(&vpn_mode, locked, context))
+                                .child(
+                                    div()
+                                        .id("anchor-client-version")
+                                        .anchor_scroll(Some(
+                                            self.configuration_scroll_anchors[14].clone(),
+                                        ))
+                                        .child(validated_field(
+                                            "Client Version",
+                                            self.client_version_input.clone(),
+                                            self.field_error(CredentialField::ClientVersion),
+                                        )),
                                 ),
                         )
                         .child(
@@ -2142,6 +3374,7 @@ impl Render for TrustTunnelApp {
                                 .pt(px(PADDING_COLUMN_TOP))
                                 .gap(px(GAP_MEDIUM))
                                 .child(self.render_mode_selector(tunnel_mode, locked, context))
+                                .child(self.render_proxy_settings(tunnel_mode, locked, context))
                                 .child(self.render_connection_toggles(locked, context))
                                 .child(
                                     button_action(
@@ -2164,6 +3397,14 @@ impl Render for TrustTunnelApp {
                                 .when(!detail.is_empty(), |container| {
                                     container.child(status_detail(detail))
                                 })
+                                .when_some(
+                                    self.offered_client_download.clone(),
+                                    |container, tag| {
+                                        container.child(
+                                            self.render_offered_download(&tag, locked, context),
+                                        )
+                                    },
+                                )
                                 .child(
                                     div()
                                         .flex()
@@ -2258,6 +3499,14 @@ impl TrustTunnelApp {
             .map(|client_manager_state_guard| client_manager_state_guard.has_selected_client())
             .unwrap_or(false);
 
+        let has_update = self
+            .client_manager_state
+            .lock()
+            .map(|client_manager_state_guard| {
+                client_manager_state_guard.available_update().is_some()
+            })
+            .unwrap_or(false);
+
         let client_button_label = self
             .client_manager_state
             .lock()
@@ -2280,6 +3529,7 @@ impl TrustTunnelApp {
                     "TrustTunnel",
                     self.active_tab == ActiveTab::Connection,
                     !has_client,
+                    false,
                 )
                 .on_mouse_up(
                     MouseButton::Left,
@@ -2294,6 +3544,7 @@ impl TrustTunnelApp {
                     &client_button_label,
                     self.active_tab == ActiveTab::Client,
                     false,
+                    has_update,
                 )
                 .on_mouse_up(
                     MouseButton::Left,
@@ -2482,6 +3733,39 @@ impl TrustTunnelApp {
                             ),
                     ),
             )
+            .child(self.render_vault_unlock(locked, context))
+    }
+
+    fn render_vault_unlock(&self, locked: bool, context: &mut Context<Self>) -> impl IntoElement {
+        let mut container = div().flex().flex_col().gap(px(GAP_EXTRA_SMALL)).w_full();
+        if self.vault_locked() {
+            let creating = !secret_store::vault_exists();
+            let (title, action) = if creating {
+                ("New Vault Passphrase", "Create")
+            } else {
+                ("Vault Passphrase", "Unlock")
+            };
+            container = container
+                .child(field(title, self.vault_passphrase_input.clone()))
+                .when(creating, |container| {
+                    container.child(field(
+                        "Repeat Passphrase",
+                        self.vault_confirmation_input.clone(),
+                    ))
+                })
+                .child(
+                    button_ghost(action, locked, &self.vault_unlock_focus_handle).when(
+                        !locked,
+                        |element| {
+                            element.on_mouse_up(
+                                MouseButton::Left,
+                                context.listener(|this, _, _, context| this.unlock_vault(context)),
+                            )
+                        },
+                    ),
+                );
+        }
+        container
     }
 
     fn render_endpoint_toggles(
@@ -2510,12 +3794,79 @@ impl TrustTunnelApp {
             .child(toggle(
                 "Anti-DPI",
                 self.anti_dpi,
-                locked,
+                locked || !self.client_supports(client_capabilities::ANTI_DPI, context),
                 &self.anti_dpi_focus_handle,
                 context.listener(|this, _, _, context| this.toggle_anti_dpi(context)),
             ))
     }
 
+    fn render_proxy_settings(
+        &self,
+        tunnel_mode: TunnelMode,
+        locked: bool,
+        context: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let mut container = div().flex().flex_col().gap(px(GAP_SMALL));
+        if !tunnel_mode.is_tun() {
+            container = container
+                .child(field(
+                    "SOCKS Listen Address",
+                    self.proxy_listen_input.clone(),
+                ))
+                .child(toggle(
+                    "SOCKS Authentication",
+                    self.proxy_authentication,
+                    locked,
+                    &self.proxy_authentication_focus_handle,
+                    context
+                        .listener(|this, _, _, context| this.toggle_proxy_authentication(context)),
+                ));
+        }
+        container
+    }
+
+    fn render_keep_versions_selector(
+        &self,
+        keep_versions: Option<usize>,
+        context: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let mut row = selector_row();
+        for (keep, focus_handle) in KEEP_CLIENT_VERSION_OPTIONS
+            .into_iter()
+            .zip(&self.client_keep_focus_handles)
+        {
+            let text = keep.map_or_else(|| "All".to_string(), |keep| keep.to_string());
+            row = row.child(
+                selector_option(&text, keep_versions == keep, false, focus_handle).on_mouse_up(
+                    MouseButton::Left,
+                    context.listener(move |this, _, _, context| {
+                        this.set_keep_client_versions(keep, context);
+                    }),
+                ),
+            );
+        }
+        selector("Keep Older Versions", row)
+    }
+
+    fn render_offered_download(
+        &self,
+        tag: &str,
+        locked: bool,
+        context: &mut Context<Self>,
+    ) -> impl IntoElement {
+        button_ghost(
+            &format!("Download {tag}"),
+            locked,
+            &self.offered_download_focus_handle,
+        )
+        .when(!locked, |element| {
+            element.on_mouse_up(
+                MouseButton::Left,
+                context.listener(|this, _, _, context| this.download_offered_client(context)),
+            )
+        })
+    }
+
     fn render_connection_toggles(
         &self,
         locked: bool,
@@ -2532,6 +3883,29 @@ impl TrustTunnelApp {
                 &self.dns_enabled_focus_handle,
                 context.listener(|this, _, _, context| this.toggle_dns_enabled(context)),
             ))
+            .child(toggle(
+                "Auto-reconnect",
+                self.reconnect_policy.enabled,
+                locked,
+                &self.auto_reconnect_focus_handle,
+                context.listener(|this, _, _, context| this.toggle_auto_reconnect(context)),
+            ))
+            .when(self.reconnect_policy.enabled, |container| {
+                let policy = self.reconnect_policy;
+                container.child(
+                    div()
+                        .px(px(PADDING_INPUT_HORIZONTAL))
+                        .text_size(px(TEXT_SIZE_SMALL))
+                        .text_color(rgb(TEXT_DIM))
+                        .child(format!(
+                            "Up to {} attempts, waiting {}s and doubling up to {}s; \
+                             set in trusttunnel-ui.toml",
+                            policy.max_attempts,
+                            policy.initial_delay.as_secs(),
+                            policy.max_delay.as_secs(),
+                        )),
+                )
+            })
             .child(toggle(
                 "Kill Switch",
                 self.killswitch_enabled,
@@ -2542,7 +3916,7 @@ impl TrustTunnelApp {
             .child(toggle(
                 "Post-Quantum",
                 self.post_quantum_group_enabled,
-                locked,
+                locked || !self.client_supports(client_capabilities::POST_QUANTUM_GROUP, context),
                 &self.post_quantum_focus_handle,
                 context.listener(|this, _, _, context| {
                     this.toggle_post_quantum_group_enabled(context)
@@ -2646,6 +4020,84 @@ impl TrustTunnelApp {
         selector("Fallback Protocol", row)
     }
 
+    fn render_vpn_mode_fields(
+        &self,
+        vpn_mode: &str,
+        locked: bool,
+        context: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let exclusions_label = if vpn_mode == "selective" {
+            "Tunneled Only (domains, *.wildcards, CIDRs)"
+        } else {
+            "Exclusions (domains, *.wildcards, CIDRs)"
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(GAP_MEDIUM))
+            .child(
+                div()
+                    .id("anchor-vpn-mode")
+                    .anchor_scroll(Some(self.configuration_scroll_anchors[12].clone()))
+                    .child(self.render_vpn_mode_selector(vpn_mode, locked, context))
+                    .when_some(
+                        self.field_error(CredentialField::VpnMode),
+                        |element, error| element.child(field_error(error)),
+                    ),
+            )
+            .child(
+                div()
+                    .id("anchor-exclusions")
+                    .anchor_scroll(Some(self.configuration_scroll_anchors[13].clone()))
+                    .child(validated_field(
+                        exclusions_label,
+                        self.exclusions_input.clone(),
+                        self.field_error(CredentialField::Exclusions),
+                    )),
+            )
+    }
+
+    fn render_vpn_mode_selector(
+        &self,
+        vpn_mode: &str,
+        locked: bool,
+        context: &mut Context<Self>,
+    ) -> impl IntoElement {
+        selector(
+            "VPN Mode",
+            selector_row()
+                .child(
+                    selector_option(
+                        "General",
+                        vpn_mode != "selective",
+                        locked,
+                        &self.vpn_mode_general_focus_handle,
+                    )
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        context.listener(|this, _, _, context| {
+                            this.set_vpn_mode("general", context);
+                        }),
+                    ),
+                )
+                .child(
+                    selector_option(
+                        "Selective",
+                        vpn_mode == "selective",
+                        locked,
+                        &self.vpn_mode_selective_focus_handle,
+                    )
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        context.listener(|this, _, _, context| {
+                            this.set_vpn_mode("selective", context);
+                        }),
+                    ),
+                ),
+        )
+    }
+
     fn render_mode_selector(
         &self,
         tunnel_mode: TunnelMode,
@@ -2703,14 +4155,53 @@ impl TrustTunnelApp {
     fn render_client_tab(&mut self, context: &mut Context<Self>) -> impl IntoElement {
         self.update_binary_from_client_manager();
 
-        let (releases, installed, selected_version, downloads, is_fetching) = {
+        let (
+            releases,
+            installed,
+            disk_usage,
+            selected_version,
+            downloads,
+            download_errors,
+            is_fetching,
+            is_importing,
+            import_error,
+            release_error,
+            available_update,
+            pending_upgrade,
+            prune_after_upgrade,
+            prerelease_channel,
+            keep_versions,
+        ) = {
             let client_manager_state_guard = self.client_manager_state.lock().unwrap();
             (
                 client_manager_state_guard.releases.clone(),
                 client_manager_state_guard.installed.clone(),
+                client_manager_state_guard.disk_usage.clone(),
                 client_manager_state_guard.selected_version.clone(),
                 client_manager_state_guard.downloads.clone(),
+                client_manager_state_guard.download_errors.clone(),
                 client_manager_state_guard.fetching_releases,
+                client_manager_state_guard.importing,
+                client_manager_state_guard.import_error.clone(),
+                client_manager_state_guard
+                    .release_error
+                    .clone()
+                    .map(
+                        |error| match client_manager_state_guard.releases_fetched_at {
+                            Some(fetched_at) => format!(
+                                "{error} (showing list from {})",
+                                fetched_at
+                                    .with_timezone(&chrono::Local)
+                                    .format("%Y-%m-%d %H:%M")
+                            ),
+                            None => error,
+                        },
+                    ),
+                client_manager_state_guard.available_update().cloned(),
+                client_manager_state_guard.pending_upgrade.clone(),
+                client_manager_state_guard.prune_after_upgrade,
+                client_manager_state_guard.release_source.channel == ReleaseChannel::Prerelease,
+                client_manager_state_guard.keep_versions,
             )
         };
 
@@ -2746,6 +4237,7 @@ impl TrustTunnelApp {
             && selected_release.is_some()
             && !selected_is_downloading;
         let can_remove = selected.is_some() && selected_is_installed && !locked;
+        let selected_download_error = selected.and_then(|v| download_errors.get(v).cloned());
 
         let mut items = div()
             .flex()
@@ -2754,12 +4246,14 @@ impl TrustTunnelApp {
             .gap(px(GAP_EXTRA_SMALL))
             .overflow_hidden();
 
-        for (entry_index, (tag, _is_installed, _release)) in version_entries.iter().enumerate() {
+        for (entry_index, (tag, _is_installed, release)) in version_entries.iter().enumerate() {
             let is_selected = selected == Some(tag.as_str());
             let tag_clone = tag.clone();
 
             let display_label = if let Some(progress) = downloads.get(tag) {
-                format!("{tag} ({progress}%)")
+                format!("{tag} ({})", download_progress_label(progress))
+            } else if let Some(size) = disk_usage.get(tag) {
+                format!("{tag} · {}", client_manager::format_bytes(*size))
             } else {
                 tag.clone()
             };
@@ -2767,6 +4261,10 @@ impl TrustTunnelApp {
             items = items.child(
                 version_item(
                     &display_label,
+                    release
+                        .as_ref()
+                        .is_some_and(|release| release.prerelease)
+                        .then_some("prerelease"),
                     is_selected,
                     locked,
                     &self.client_version_focus_handles[entry_index],
@@ -2784,6 +4282,7 @@ impl TrustTunnelApp {
 
         let release_for_download = selected_release.clone();
         let tag_for_remove = selected.map(|v| v.to_string());
+        let tag_for_cancel = selected.map(|v| v.to_string());
 
         let buttons = div()
             .flex()
@@ -2793,20 +4292,48 @@ impl TrustTunnelApp {
             .gap(px(GAP_EXTRA_SMALL))
             .child(
                 button_ghost(
-                    "Add",
-                    locked || !can_download,
-                    &self.client_download_focus_handle,
+                    "Import",
+                    locked || is_importing,
+                    &self.client_import_focus_handle,
                 )
-                .when(can_download, |element| {
-                    let release = release_for_download.unwrap();
+                .when(!locked && !is_importing, |element| {
                     element.on_mouse_up(
                         MouseButton::Left,
-                        context.listener(move |this, _, _, context| {
-                            this.download_client_version(release.clone(), context);
+                        context.listener(|this, _, _, context| {
+                            this.import_client(context);
                         }),
                     )
                 }),
             )
+            .when(!selected_is_downloading, |buttons| {
+                buttons.child(
+                    button_ghost(
+                        "Add",
+                        locked || !can_download,
+                        &self.client_download_focus_handle,
+                    )
+                    .when(can_download, |element| {
+                        let release = release_for_download.unwrap();
+                        element.on_mouse_up(
+                            MouseButton::Left,
+                            context.listener(move |this, _, _, context| {
+                                this.download_client_version(release.clone(), context);
+                            }),
+                        )
+                    }),
+                )
+            })
+            .when(selected_is_downloading, |buttons| {
+                let tag = tag_for_cancel.unwrap();
+                buttons.child(
+                    button_ghost("Cancel", false, &self.client_download_focus_handle).on_mouse_up(
+                        MouseButton::Left,
+                        context.listener(move |this, _, _, context| {
+                            this.cancel_client_download(tag.clone(), context);
+                        }),
+                    ),
+                )
+            })
             .child(
                 button_ghost(
                     "Remove",
@@ -2840,6 +4367,86 @@ impl TrustTunnelApp {
                     .gap(px(GAP_EXTRA_SMALL))
                     .w_full()
                     .overflow_hidden()
+                    .when_some(available_update, |container, release| {
+                        let upgrade_disabled = locked || pending_upgrade.is_some();
+                        let message = match pending_upgrade.as_deref() {
+                            Some(tag) => format!("Upgrading to {tag}…"),
+                            None => format!("{} is available", release.tag),
+                        };
+                        container
+                            .child(label("Update"))
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_row()
+                                    .items_center()
+                                    .gap(px(GAP_SMALL))
+                                    .child(
+                                        div()
+                                            .flex_1()
+                                            .px(px(PADDING_INPUT_HORIZONTAL))
+                                            .text_size(px(TEXT_SIZE_SMALL))
+                                            .text_color(rgb(TEXT_PRIMARY))
+                                            .child(message),
+                                    )
+                                    .child(
+                                        div().w(px(CREDENTIAL_BUTTON_WIDTH)).child(
+                                            button_ghost(
+                                                "Upgrade",
+                                                upgrade_disabled,
+                                                &self.client_upgrade_focus_handle,
+                                            )
+                                            .when(
+                                                !upgrade_disabled,
+                                                |element| {
+                                                    element.on_mouse_up(
+                                                        MouseButton::Left,
+                                                        context.listener(|this, _, _, context| {
+                                                            this.upgrade_client(context);
+                                                        }),
+                                                    )
+                                                },
+                                            ),
+                                        ),
+                                    ),
+                            )
+                            .child(toggle(
+                                "Remove older versions after upgrade",
+                                prune_after_upgrade,
+                                false,
+                                &self.client_prune_focus_handle,
+                                context.listener(|this, _, _, context| {
+                                    this.toggle_prune_after_upgrade(context)
+                                }),
+                            ))
+                    })
+                    .child(toggle(
+                        "Include prereleases",
+                        prerelease_channel,
+                        false,
+                        &self.client_channel_focus_handle,
+                        context.listener(|this, _, _, context| {
+                            this.toggle_prerelease_channel(context)
+                        }),
+                    ))
+                    .child(self.render_keep_versions_selector(keep_versions, context))
+                    .child(field("Release Source", self.release_url_input.clone()))
+                    .child(validated_field(
+                        "GitHub Token",
+                        self.github_token_input.clone(),
+                        self.release_source_error.as_deref(),
+                    ))
+                    .child(
+                        div().w(px(CREDENTIAL_BUTTON_WIDTH)).child(
+                            button_ghost("Apply", false, &self.client_release_source_focus_handle)
+                                .on_mouse_up(
+                                    MouseButton::Left,
+                                    context.listener(|this, _, _, context| {
+                                        this.apply_release_source(context)
+                                    }),
+                                ),
+                        ),
+                    )
                     .child(label("Client Versions"))
                     .when(is_fetching && version_entries.is_empty(), |container| {
                         container.child(
@@ -2850,6 +4457,42 @@ impl TrustTunnelApp {
                                 .child("Downloading version list…"),
                         )
                     })
+                    .when_some(release_error, |container, error| {
+                        container.child(
+                            div()
+                                .px(px(PADDING_INPUT_HORIZONTAL))
+                                .text_size(px(TEXT_SIZE_SMALL))
+                                .text_color(rgb(COLOR_RED))
+                                .child(error),
+                        )
+                    })
+                    .when(is_importing, |container| {
+                        container.child(
+                            div()
+                                .px(px(PADDING_INPUT_HORIZONTAL))
+                                .text_size(px(TEXT_SIZE_SMALL))
+                                .text_color(rgb(COLOR_YELLOW))
+                                .child("Installing local client…"),
+                        )
+                    })
+                    .when_some(import_error, |container, error| {
+                        container.child(
+                            div()
+                                .px(px(PADDING_INPUT_HORIZONTAL))
+                                .text_size(px(TEXT_SIZE_SMALL))
+                                .text_color(rgb(COLOR_RED))
+                                .child(error),
+                        )
+                    })
+                    .when_some(selected_download_error, |container, error| {
+                        container.child(
+                            div()
+                                .px(px(PADDING_INPUT_HORIZONTAL))
+                                .text_size(px(TEXT_SIZE_SMALL))
+                                .text_color(rgb(COLOR_RED))
+                                .child(error),
+                        )
+                    })
                     .child(
                         div()
                             .id("client-versions-scroll")
@@ -2861,7 +4504,77 @@ impl TrustTunnelApp {
                             .track_scroll(&self.client_scroll_handle)
                             .child(items)
                             .child(buttons),
-                    ),
+                    )
+                    .when_some(selected_release, |container, release| {
+                        container
+                            .child(label("Release Details"))
+                            .child(
+                                div()
+                                    .px(px(PADDING_INPUT_HORIZONTAL))
+                                    .child(status_detail(release_metadata_label(&release))),
+                            )
+                            .child(
+                                div()
+                                    .id("client-release-notes")
+                                    .flex_shrink_0()
+                                    .max_h(px(RELEASE_NOTES_HEIGHT))
+                                    .px(px(PADDING_INPUT_HORIZONTAL))
+                                    .py(px(PADDING_INPUT_VERTICAL))
+                                    .rounded(px(RADIUS))
+                                    .border_1()
+                                    .border_color(rgb(BORDER))
+                                    .bg(rgb(INPUT_BACKGROUND))
+                                    .overflow_y_scroll()
+                                    .child(release_notes(&release.notes)),
+                            )
+                    }),
             )
     }
 }
+
+fn release_metadata_label(release: &ClientRelease) -> String {
+    let mut parts = Vec::new();
+    if let Some(published_at) = release.published_at {
+        parts.push(format!(
+            "Published {}",
+            published_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d")
+        ));
+    }
+    parts.push(release.asset_name.clone());
+    if release.asset_size > 0 {
+        parts.push(client_manager::format_bytes(release.asset_size));
+    }
+    parts.push(match release.checksum_name.as_deref() {
+        Some(checksum_name) => format!("SHA-256 from {checksum_name}"),
+        None => "no checksum published".to_string(),
+    });
+    parts.join(" · ")
+}
+
+fn download_progress_label(progress: &DownloadProgress) -> String {
+    if progress.is_cancelling() {
+        return "cancelling…".to_string();
+    }
+    let mut parts = Vec::new();
+    match progress.percent() {
+        Some(percent) => parts.push(format!(
+            "{percent}% · {} / {}",
+            client_manager::format_bytes(progress.received),
+            client_manager::format_bytes(progress.total)
+        )),
+        None => parts.push(client_manager::format_bytes(progress.received)),
+    }
+    if progress.bytes_per_second >= 1.0 {
+        parts.push(format!(
+            "{}/s",
+            client_manager::format_bytes(progress.bytes_per_second as u64)
+        ));
+    }
+    if let Some(eta) = progress.eta() {
+        let seconds = eta.as_secs();
+        parts.push(format!("{}:{:02} left", seconds / 60, seconds % 60));
+    }
+    parts.join(" · ")
+}
@@ -1,8 +1,13 @@
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
-use crate::configuration::{StoredCredential, TunnelMode};
+use crate::client_manager::{ReleaseChannel, ReleaseSource};
+use crate::configuration::{
+    DEFAULT_PROXY_LISTEN_ADDRESS, StoredCredential, TunnelMode, write_private_file,
+};
+use crate::connection_state::ReconnectPolicy;
 
 #[derive(Serialize, Deserialize)]
 pub struct AppState {
@@ -16,12 +21,48 @@ pub struct AppState {
     pub dns_enabled: bool,
     #[serde(default)]
     pub selected_client_version: Option<String>,
+    #[serde(default)]
+    pub proxy_listen_address: Option<String>,
+    #[serde(default)]
+    pub proxy_authentication: bool,
+    #[serde(default)]
+    pub auto_reconnect: bool,
+    #[serde(default = "default_reconnect_max_attempts")]
+    pub reconnect_max_attempts: u32,
+    #[serde(default = "default_reconnect_initial_delay_seconds")]
+    pub reconnect_initial_delay_seconds: u64,
+    #[serde(default = "default_reconnect_max_delay_seconds")]
+    pub reconnect_max_delay_seconds: u64,
+    #[serde(default)]
+    pub release_source_url: Option<String>,
+    /// Kept here in plain text, which is why the file is written owner-only.
+    #[serde(default)]
+    pub github_token: Option<String>,
+    #[serde(default)]
+    pub client_channel: Option<String>,
+    #[serde(default)]
+    pub prune_after_upgrade: bool,
+    /// Installed client versions to keep besides the selected one; 0 keeps all.
+    #[serde(default)]
+    pub keep_client_versions: u32,
 }
 
 fn default_dns_enabled() -> bool {
     true
 }
 
+fn default_reconnect_max_attempts() -> u32 {
+    5
+}
+
+fn default_reconnect_initial_delay_seconds() -> u64 {
+    2
+}
+
+fn default_reconnect_max_delay_seconds() -> u64 {
+    60
+}
+
 impl Default for AppState {
     fn default() -> Self {
         Self {
@@ -30,6 +71,17 @@ impl Default for AppState {
             tunnel_mode: None,
             dns_enabled: true,
             selected_client_version: None,
+            proxy_listen_address: None,
+            proxy_authentication: false,
+            auto_reconnect: false,
+            reconnect_max_attempts: default_reconnect_max_attempts(),
+            reconnect_initial_delay_seconds: default_reconnect_initial_delay_seconds(),
+            reconnect_max_delay_seconds: default_reconnect_max_delay_seconds(),
+            release_source_url: None,
+            github_token: None,
+            client_channel: None,
+            prune_after_upgrade: false,
+            keep_client_versions: 0,
         }
     }
 }
@@ -77,8 +129,8 @@ impl AppState {
         }
         match toml::to_string_pretty(self) {
             Ok(content) => {
-                if let Err(error) = std::fs::write(&path, content) {
-                    log::warn!("[app_state] failed to write {}: {error}", path.display());
+                if let Err(error) = write_private_file(&path, &content) {
+                    log::warn!("[app_state] {error}");
                 }
             }
             Err(error) => {
@@ -103,6 +155,80 @@ impl AppState {
         self.dns_enabled = enabled;
     }
 
+    pub fn proxy_listen_address(&self) -> &str {
+        self.proxy_listen_address
+            .as_deref()
+            .filter(|address| !address.trim().is_empty())
+            .unwrap_or(DEFAULT_PROXY_LISTEN_ADDRESS)
+    }
+
+    pub fn set_proxy_listen_address(&mut self, address: &str) {
+        let address = address.trim();
+        self.proxy_listen_address = (!address.is_empty()
+            && address != DEFAULT_PROXY_LISTEN_ADDRESS)
+            .then(|| address.to_string());
+    }
+
+    pub fn proxy_authentication(&self) -> bool {
+        self.proxy_authentication
+    }
+
+    pub fn set_proxy_authentication(&mut self, enabled: bool) {
+        self.proxy_authentication = enabled;
+    }
+
+    pub fn reconnect_policy(&self) -> ReconnectPolicy {
+        let initial_delay = Duration::from_secs(self.reconnect_initial_delay_seconds.max(1));
+        ReconnectPolicy {
+            enabled: self.auto_reconnect,
+            max_attempts: self.reconnect_max_attempts,
+            initial_delay,
+            max_delay: Duration::from_secs(self.reconnect_max_delay_seconds).max(initial_delay),
+        }
+    }
+
+    pub fn set_reconnect_policy(&mut self, policy: ReconnectPolicy) {
+        self.auto_reconnect = policy.enabled;
+        self.reconnect_max_attempts = policy.max_attempts;
+        self.reconnect_initial_delay_seconds = policy.initial_delay.as_secs();
+        self.reconnect_max_delay_seconds = policy.max_delay.as_secs();
+    }
+
+    pub fn release_source(&self) -> ReleaseSource {
+        ReleaseSource {
+            releases_url: self.release_source_url.clone(),
+            github_token: self.github_token.clone(),
+            channel: self
+                .client_channel
+                .as_deref()
+                .and_then(ReleaseChannel::parse)
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn set_release_source(&mut self, source: &ReleaseSource) {
+        self.release_source_url = source.releases_url.clone();
+        self.github_token = source.github_token.clone();
+        self.client_channel =
+            (source.channel != ReleaseChannel::Stable).then(|| source.channel.as_str().to_string());
+    }
+
+    pub fn prune_after_upgrade(&self) -> bool {
+        self.prune_after_upgrade
+    }
+
+    pub fn set_prune_after_upgrade(&mut self, enabled: bool) {
+        self.prune_after_upgrade = enabled;
+    }
+
+    pub fn keep_client_versions(&self) -> Option<usize> {
+        (self.keep_client_versions > 0).then_some(self.keep_client_versions as usize)
+    }
+
+    pub fn set_keep_client_versions(&mut self, keep: Option<usize>) {
+        self.keep_client_versions = keep.unwrap_or(0) as u32;
+    }
+
     pub fn tunnel_mode(&self) -> TunnelMode {
         match self.tunnel_mode.as_deref() {
             Some("tun") => TunnelMode::Tun,
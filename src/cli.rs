@@ -0,0 +1,561 @@
+use std::{
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::{AppState, apply_saved_order},
+    client_manager::{self, client_binary_path, scan_installed_clients},
+    configuration::{
+        DEFAULT_PROXY_LISTEN_ADDRESS, ProxyListener, StoredCredential, TunnelMode,
+        VpnConfiguration, credentials_directory, find_credential_index, parse_proxy_listen_address,
+        redact_password_in_toml, scan_credentials, write_private_file,
+    },
+    process_log::ProcessLog,
+    system::{self, ChildProcess, SystemServices, dns, proxy},
+};
+
+const USAGE: &str = "\
+Usage:
+  trusttunnel-ui                                   start the GUI
+  trusttunnel-ui <credential.toml>                 import a credential and start the GUI
+  trusttunnel-ui list                              list stored credentials
+  trusttunnel-ui connect <name> [--mode <mode>]    connect in the foreground (tun, proxy, system-proxy)
+  trusttunnel-ui status                            show the headless session, exit 1 if none
+  trusttunnel-ui disconnect                        stop the headless session
+
+Passwords kept in the encrypted vault are unlocked with $TRUSTTUNNEL_VAULT_PASSPHRASE.";
+
+const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub enum Command {
+    Help,
+    List,
+    Connect {
+        name: String,
+        mode: Option<TunnelMode>,
+    },
+    Status,
+    Disconnect,
+}
+
+/// Written by a foreground `connect` so `status` and `disconnect` can find it.
+#[derive(Serialize, Deserialize)]
+struct Session {
+    owner_process_id: u32,
+    client_process_id: Option<u32>,
+    credential: String,
+    mode: String,
+    started_at: String,
+    #[serde(default)]
+    proxy_url: Option<String>,
+}
+
+/// `None` means the arguments are for the GUI (no arguments or a file to import).
+pub fn parse(arguments: &[String]) -> Option<Result<Command, String>> {
+    let subcommand = arguments.first()?;
+    let rest = &arguments[1..];
+    let command = match subcommand.as_str() {
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        "list" => expect_no_arguments("list", rest).map(|_| Command::List),
+        "status" => expect_no_arguments("status", rest).map(|_| Command::Status),
+        "disconnect" => expect_no_arguments("disconnect", rest).map(|_| Command::Disconnect),
+        "connect" => parse_connect(rest),
+        _ => return None,
+    };
+    Some(command)
+}
+
+fn expect_no_arguments(subcommand: &str, rest: &[String]) -> Result<(), String> {
+    match rest.first() {
+        Some(argument) => Err(format!(
+            "'{subcommand}' takes no arguments, got '{argument}'"
+        )),
+        None => Ok(()),
+    }
+}
+
+fn parse_connect(rest: &[String]) -> Result<Command, String> {
+    let mut name: Option<String> = None;
+    let mut mode: Option<TunnelMode> = None;
+    let mut iterator = rest.iter();
+
+    while let Some(argument) = iterator.next() {
+        let mode_value = if argument == "--mode" || argument == "-m" {
+            Some(
+                iterator
+                    .next()
+                    .ok_or_else(|| "--mode requires a value".to_string())?
+                    .as_str(),
+            )
+        } else {
+            argument.strip_prefix("--mode=")
+        };
+
+        if let Some(value) = mode_value {
+            mode = Some(parse_mode(value)?);
+        } else if argument.starts_with('-') {
+            return Err(format!("unknown option '{argument}'"));
+        } else if name.is_none() {
+            name = Some(argument.clone());
+        } else {
+            return Err(format!("unexpected argument '{argument}'"));
+        }
+    }
+
+    let name = name.ok_or_else(|| "'connect' requires a credential name".to_string())?;
+    Ok(Command::Connect { name, mode })
+}
+
+fn parse_mode(value: &str) -> Result<TunnelMode, String> {
+    TunnelMode::from_name(value)
+        .ok_or_else(|| format!("unknown mode '{value}' (expected tun, proxy or system-proxy)"))
+}
+
+pub fn usage_error(error: &str) -> i32 {
+    eprintln!("error: {error}\n\n{USAGE}");
+    2
+}
+
+/// Returns the process exit code.
+pub fn run(command: Command, system_services: Arc<dyn SystemServices>) -> i32 {
+    let result = match command {
+        Command::Help => {
+            println!("{USAGE}");
+            Ok(0)
+        }
+        Command::List => list(),
+        Command::Connect { name, mode } => connect(&name, mode, system_services),
+        Command::Status => status(system_services.as_ref()),
+        Command::Disconnect => disconnect(system_services.as_ref()),
+    };
+
+    result.unwrap_or_else(|error| {
+        eprintln!("error: {error}");
+        1
+    })
+}
+
+fn cli_directory() -> PathBuf {
+    let directory = credentials_directory().join("cli");
+    if let Err(error) = std::fs::create_dir_all(&directory) {
+        log::warn!(
+            "[cli] failed to create directory {}: {error}",
+            directory.display()
+        );
+    }
+    directory
+}
+
+fn session_path() -> PathBuf {
+    cli_directory().join("session.json")
+}
+
+fn read_session() -> Option<Session> {
+    let content = std::fs::read_to_string(session_path()).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(session) => Some(session),
+        Err(error) => {
+            log::warn!("[cli] ignoring unreadable session file: {error}");
+            None
+        }
+    }
+}
+
+fn write_session(session: &Session) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(session)
+        .map_err(|error| format!("Failed to serialize session: {error}"))?;
+    // Holds the per-session SOCKS credentials in `proxy_url`.
+    write_private_file(&session_path(), &content)
+}
+
+fn remove_session() {
+    let path = session_path();
+    if path.exists()
+        && let Err(error) = std::fs::remove_file(&path)
+    {
+        log::warn!("[cli] failed to remove {}: {error}", path.display());
+    }
+}
+
+fn stored_credentials(state: &AppState) -> Vec<StoredCredential> {
+    let mut credentials = scan_credentials(&credentials_directory());
+    apply_saved_order(&mut credentials, &state.credential_order);
+    credentials
+}
+
+fn list() -> Result<i32, String> {
+    let state = AppState::load();
+    let credentials = stored_credentials(&state);
+    if credentials.is_empty() {
+        println!(
+            "No stored credentials in {}",
+            credentials_directory().display()
+        );
+        return Ok(0);
+    }
+
+    let selected = state.find_selected_index(&credentials);
+    for (index, stored) in credentials.iter().enumerate() {
+        let marker = if selected == Some(index) { "*" } else { " " };
+        println!(
+            "{marker} {}\t{}",
+            stored.name,
+            stored.credential.addresses.join(", ")
+        );
+    }
+    Ok(0)
+}
+
+fn find_credential(credentials: Vec<StoredCredential>, name: &str) -> Option<StoredCredential> {
+    let position = find_credential_index(&credentials, name)?;
+    credentials.into_iter().nth(position)
+}
+
+fn client_binary(state: &AppState, system_services: &dyn SystemServices) -> (String, bool) {
+    if let Some(version) = state.selected_client_version()
+        && scan_installed_clients()
+            .iter()
+            .any(|installed| installed == version)
+    {
+        let path = client_binary_path(version);
+        let exists = path.exists();
+        return (path.to_string_lossy().to_string(), exists);
+    }
+    system_services.find_client_binary()
+}
+
+fn connect(
+    name: &str,
+    mode: Option<TunnelMode>,
+    system_services: Arc<dyn SystemServices>,
+) -> Result<i32, String> {
+    if let Some(session) = read_session()
+        && system_services.is_process_running(session.owner_process_id)
+    {
+        return Err(format!(
+            "already connected with '{}' (process {}), run 'disconnect' first",
+            session.credential, session.owner_process_id,
+        ));
+    }
+    remove_session();
+
+    let state = AppState::load();
+    let mode = mode.unwrap_or_else(|| state.tunnel_mode());
+    let stored = find_credential(stored_credentials(&state), name)
+        .ok_or_else(|| format!("no stored credential named '{name}', see 'list'"))?;
+
+    if let Some((message, detail)) = stored.credential.validate() {
+        return Err(format!("{message}: {detail}"));
+    }
+
+    let pinned_version = stored.credential.client_version.trim();
+    let binary_path = if pinned_version.is_empty() {
+        let (binary_path, binary_found) = client_binary(&state, system_services.as_ref());
+        if !binary_found {
+            return Err(format!("client binary '{binary_path}' not found"));
+        }
+        binary_path
+    } else {
+        let tag = scan_installed_clients()
+            .into_iter()
+            .find(|tag| tag.trim_start_matches('v') == pinned_version.trim_start_matches('v'))
+            .ok_or_else(|| {
+                format!(
+                    "'{}' is pinned to client {pinned_version}, which is not installed; \
+                     download it from the Client tab",
+                    stored.name
+                )
+            })?;
+        client_binary_path(&tag).to_string_lossy().to_string()
+    };
+
+    if mode.is_tun() && !system_services.check_tun_device() {
+        return Err("TUN device not available".into());
+    }
+    let needs_elevation = mode.is_tun() && !system::is_privileged();
+    if needs_elevation && cfg!(target_os = "windows") {
+        return Err("TUN mode requires an Administrator command prompt".into());
+    }
+    if needs_elevation && !system_services.check_elevation_available() {
+        return Err("TUN mode requires root or pkexec".into());
+    }
+
+    let proxy_listener = if mode.is_tun() {
+        ProxyListener::default()
+    } else {
+        let listen_address =
+            parse_proxy_listen_address(state.proxy_listen_address()).or_else(|error| {
+                log::warn!("[cli] {error}, falling back to {DEFAULT_PROXY_LISTEN_ADDRESS}");
+                parse_proxy_listen_address(DEFAULT_PROXY_LISTEN_ADDRESS)
+            })?;
+        ProxyListener::prepare(listen_address, state.proxy_authentication())
+            .map_err(|(message, detail)| format!("{message}: {detail}"))?
+    };
+
+    let dns_enabled = state.dns_enabled();
+    let endpoint = stored.credential.to_endpoint_fields(dns_enabled);
+    let mut configuration = VpnConfiguration::new(endpoint, mode, &proxy_listener);
+    configuration.exclude_endpoint_addresses();
+
+    let capabilities = client_manager::probe_capabilities(Path::new(&binary_path));
+    let (toml_string, warnings) = configuration.to_toml(&capabilities)?;
+    for warning in warnings {
+        log::warn!("[cli] {warning}");
+        eprintln!("warning: {warning}");
+    }
+    log::info!(
+        "[cli] generated configuration:\n{}",
+        redact_password_in_toml(&toml_string),
+    );
+    let configuration_path = cli_directory().join("client.toml");
+    write_private_file(&configuration_path, &toml_string)?;
+
+    system::install_interrupt_handler();
+
+    println!("Connecting '{}' ({})…", stored.name, mode.name());
+    let mut child = system_services
+        .spawn_client(&binary_path, &configuration_path, needs_elevation)
+        .map_err(|error| format!("Could not start TrustTunnel client: {error}"))?;
+
+    let process_log = Arc::new(Mutex::new(ProcessLog::new()));
+    start_output_readers(&mut child, &process_log);
+
+    let session = Session {
+        owner_process_id: std::process::id(),
+        client_process_id: child.id(),
+        credential: stored.name.clone(),
+        mode: mode.name().to_string(),
+        started_at: chrono::Local::now().to_rfc3339(),
+        proxy_url: (!mode.is_tun()).then(|| proxy_listener.url()),
+    };
+    if let Err(error) = write_session(&session) {
+        log::warn!("[cli] {error}");
+    }
+
+    let exit_code = supervise(
+        &mut child,
+        &process_log,
+        mode,
+        &stored,
+        &proxy_listener,
+        dns_enabled,
+        system_services,
+    );
+    remove_session();
+    Ok(exit_code)
+}
+
+fn start_output_readers(child: &mut ChildProcess, process_log: &Arc<Mutex<ProcessLog>>) {
+    let streams = [child.take_stdout(), child.take_stderr()];
+    for stream in streams.into_iter().flatten() {
+        let process_log = process_log.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                println!("{line}");
+                let Ok(mut locked_log) = process_log.lock() else {
+                    break;
+                };
+                locked_log.push_line(line);
+            }
+        });
+    }
+}
+
+/// Waits for the client, applies proxy and DNS overrides once it reports a
+/// connection, and restores them when it exits or the user interrupts.
+fn supervise(
+    child: &mut ChildProcess,
+    process_log: &Arc<Mutex<ProcessLog>>,
+    mode: TunnelMode,
+    stored: &StoredCredential,
+    proxy_listener: &ProxyListener,
+    dns_enabled: bool,
+    system_services: Arc<dyn SystemServices>,
+) -> i32 {
+    let mut connected = false;
+    let mut proxy_overrides: Vec<Box<dyn proxy::ProxyBackend>> = Vec::new();
+    let mut dns_override: Option<Box<dyn dns::DnsBackend>> = None;
+    let mut terminate_sent_at: Option<Instant> = None;
+
+    let exit_code = loop {
+        if let Ok(Some(exit)) = child.try_wait() {
+            if connected || terminate_sent_at.is_some() {
+                println!("Client exited ({exit})");
+            } else {
+                eprintln!("Client exited before connecting ({exit})");
+            }
+            break if exit.success() || terminate_sent_at.is_some() {
+                0
+            } else {
+                1
+            };
+        }
+
+        if system::interrupted() && terminate_sent_at.is_none() {
+            println!("Disconnecting…");
+            terminate_client(child, system_services.as_ref());
+            terminate_sent_at = Some(Instant::now());
+        }
+
+        if let Some(sent_at) = terminate_sent_at
+            && sent_at.elapsed() >= GRACEFUL_SHUTDOWN_TIMEOUT
+        {
+            log::warn!("[cli] client did not exit in time, killing it");
+            child.kill();
+            child.wait();
+            break 0;
+        }
+
+        let (log_connected, log_error) = process_log
+            .lock()
+            .map(|locked_log| (locked_log.connected, locked_log.error.clone()))
+            .unwrap_or((false, None));
+
+        if !connected && terminate_sent_at.is_none() {
+            if let Some(error) = log_error {
+                eprintln!("Connection failed: {error}");
+                terminate_client(child, system_services.as_ref());
+                terminate_sent_at = Some(Instant::now());
+            } else if log_connected {
+                connected = true;
+                if mode.sets_system_proxy() {
+                    let (backends, detail) = proxy::set_all(
+                        &proxy_listener.host(),
+                        proxy_listener.port(),
+                        proxy_listener.credentials.as_ref(),
+                    );
+                    println!("{detail}");
+                    proxy_overrides = backends;
+                }
+                if mode.is_tun()
+                    && dns_enabled
+                    && !cfg!(target_os = "windows")
+                    && let Some(mut backend) = dns::detect()
+                {
+                    let upstreams: Vec<&str> = stored
+                        .credential
+                        .dns_upstreams
+                        .iter()
+                        .map(|upstream| {
+                            upstream
+                                .trim()
+                                .strip_prefix("tls://")
+                                .unwrap_or(upstream.trim())
+                        })
+                        .filter(|upstream| !upstream.is_empty())
+                        .collect();
+                    match backend.set(&upstreams) {
+                        Ok(detail) => {
+                            println!("{detail}");
+                            dns_override = Some(backend);
+                        }
+                        Err(detail) => eprintln!("DNS override failed: {detail}"),
+                    }
+                }
+                println!("Connected '{}' ({})", stored.name, mode.name());
+                if !mode.is_tun() {
+                    println!("ALL_PROXY={}", proxy_listener.url());
+                }
+            }
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    if !proxy_overrides.is_empty() {
+        log::info!("[cli] restoring system proxy");
+        proxy::clear_all(&mut proxy_overrides);
+    }
+    if let Some(mut dns) = dns_override.take() {
+        log::info!("[cli] restoring DNS via {}", dns.name());
+        dns.clear();
+    }
+    exit_code
+}
+
+fn terminate_client(child: &mut ChildProcess, system_services: &dyn SystemServices) {
+    let Some(process_id) = child.id() else {
+        child.kill();
+        return;
+    };
+    if !system_services.terminate_process(process_id) {
+        log::info!("[cli] terminate failed for process_id={process_id}, trying elevation");
+        system_services.elevate_terminate_process(process_id);
+    }
+}
+
+fn status(system_services: &dyn SystemServices) -> Result<i32, String> {
+    let Some(session) = read_session() else {
+        println!("Not connected");
+        return Ok(1);
+    };
+
+    if !system_services.is_process_running(session.owner_process_id) {
+        println!(
+            "Not connected (stale session for '{}' removed)",
+            session.credential
+        );
+        remove_session();
+        return Ok(1);
+    }
+
+    let client = session
+        .client_process_id
+        .map(|process_id| process_id.to_string())
+        .unwrap_or_else(|| "unknown".into());
+    println!("Connected: {}", session.credential);
+    println!("Mode: {}", session.mode);
+    println!("Since: {}", session.started_at);
+    println!("Process: {} (client {client})", session.owner_process_id);
+    if let Some(ref url) = session.proxy_url {
+        println!("ALL_PROXY={url}");
+    }
+    Ok(0)
+}
+
+fn disconnect(system_services: &dyn SystemServices) -> Result<i32, String> {
+    let Some(session) = read_session() else {
+        println!("Not connected");
+        return Ok(0);
+    };
+
+    let owner_running = system_services.is_process_running(session.owner_process_id);
+    if let Some(process_id) = session.client_process_id
+        && system_services.is_process_running(process_id)
+        && !system_services.terminate_process(process_id)
+    {
+        log::info!("[cli] terminate failed for process_id={process_id}, trying elevation");
+        system_services.elevate_terminate_process(process_id);
+    }
+
+    // The foreground `connect` restores proxy and DNS and removes the session.
+    if owner_running {
+        let started = Instant::now();
+        while session_path().exists() && started.elapsed() < GRACEFUL_SHUTDOWN_TIMEOUT * 2 {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        if !session_path().exists() {
+            println!("Disconnected '{}'", session.credential);
+            return Ok(0);
+        }
+        log::warn!("[cli] session owner did not finish cleanup in time");
+    }
+
+    system_services.revert_process_overrides(
+        session.owner_process_id,
+        session.mode == "system-proxy",
+        session.mode == "tun",
+    );
+    remove_session();
+    println!("Disconnected '{}'", session.credential);
+    Ok(0)
+}
@@ -0,0 +1,113 @@
+use std::path::Path;
+
+use crate::client_manager;
+
+pub const ANTI_DPI: &str = "endpoint.anti_dpi";
+pub const UPSTREAM_FALLBACK_PROTOCOL: &str = "endpoint.upstream_fallback_protocol";
+pub const POST_QUANTUM_GROUP: &str = "post_quantum_group_enabled";
+pub const KILLSWITCH_ALLOW_PORTS: &str = "killswitch_allow_ports";
+pub const DNS_UPSTREAMS: &str = "dns_upstreams";
+
+/// Keys of the generated `client.toml` that older clients reject as unknown
+/// ("failed parsing"), with the first release that accepts each of them.
+/// Everything not listed here is understood by every supported release.
+const OPTIONAL_KEYS: &[(&str, &str)] = &[
+    (ANTI_DPI, "v0.99.28"),
+    (UPSTREAM_FALLBACK_PROTOCOL, "v0.99.33"),
+    (POST_QUANTUM_GROUP, "v0.99.46"),
+    (KILLSWITCH_ALLOW_PORTS, "v0.99.46"),
+    (DNS_UPSTREAMS, "v0.99.52"),
+];
+
+/// What a particular client binary understands. An unknown version is
+/// assumed to be current, which is how configurations were built before.
+#[derive(Clone, Debug, Default)]
+pub struct ClientCapabilities {
+    pub version: Option<String>,
+    unsupported: Vec<&'static str>,
+}
+
+impl ClientCapabilities {
+    /// A key the `--help` text mentions is taken as supported even when the
+    /// table says otherwise, so builds from source are not held back.
+    pub fn for_version(version: &str, help_text: &str) -> Self {
+        let version_key = client_manager::version_sort_key(version).0;
+        let unsupported = OPTIONAL_KEYS
+            .iter()
+            .filter(|(key, introduced_in)| {
+                let name = key.rsplit('.').next().unwrap_or(key);
+                version_key < client_manager::version_sort_key(introduced_in).0
+                    && !help_text.contains(name)
+                    && !help_text.contains(&name.replace('_', "-"))
+            })
+            .map(|(key, _)| *key)
+            .collect();
+        Self {
+            version: Some(version.to_string()),
+            unsupported,
+        }
+    }
+
+    pub fn supports(&self, key: &str) -> bool {
+        !self.unsupported.contains(&key)
+    }
+
+    /// Removes the keys this client would reject from a serialized
+    /// configuration. Returns a warning for each one that carried a setting
+    /// the client will therefore not apply.
+    pub fn adapt(&self, configuration: &mut toml::Table) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for key in &self.unsupported {
+            let (table, name) = match key.split_once('.') {
+                Some((section, name)) => (
+                    configuration
+                        .get_mut(section)
+                        .and_then(toml::Value::as_table_mut),
+                    name,
+                ),
+                None => (Some(&mut *configuration), *key),
+            };
+            let Some(value) = table.and_then(|table| table.remove(name)) else {
+                continue;
+            };
+            let is_unset = match &value {
+                toml::Value::Boolean(enabled) => !enabled,
+                toml::Value::String(text) => text.is_empty(),
+                toml::Value::Array(items) => items.is_empty(),
+                _ => false,
+            };
+            if !is_unset {
+                warnings.push(format!(
+                    "client {} does not support {key}, the setting is ignored",
+                    self.version.as_deref().unwrap_or("(unknown version)")
+                ));
+            }
+        }
+        warnings
+    }
+}
+
+/// Determines what the binary understands. `known_version` is the managed
+/// version tag when there is one, which saves running `--version`.
+pub fn probe(binary_path: &Path, known_version: Option<&str>) -> ClientCapabilities {
+    let version = match known_version {
+        Some(version) => Ok(version.to_string()),
+        None => client_manager::detect_client_version(binary_path),
+    };
+    let version = match version {
+        Ok(version) => version,
+        Err(error) => {
+            log::warn!("[client_capabilities] {error}, assuming a current client");
+            return ClientCapabilities::default();
+        }
+    };
+    let help_text = client_manager::run_client_probe(binary_path, "--help").unwrap_or_default();
+
+    let capabilities = ClientCapabilities::for_version(&version, &help_text);
+    log::info!(
+        "[client_capabilities] {} ({version}) lacks {:?}",
+        binary_path.display(),
+        capabilities.unsupported
+    );
+    capabilities
+}
@@ -1,30 +1,193 @@
 use std::{
     cmp::Reverse,
     collections::HashMap,
-    io::Write,
-    path::PathBuf,
-    sync::{Arc, Mutex},
+    io::{Read, Write},
+    path::{Component, Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
 };
 
 use futures::AsyncReadExt;
 use gpui::http_client::{self, AsyncBody, HttpClient, HttpRequestExt, RedirectPolicy};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-#[derive(Clone, Debug)]
+use crate::{
+    client_capabilities::{self, ClientCapabilities},
+    client_target,
+    configuration::{credentials_directory, pinned_client_versions},
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ClientRelease {
     pub tag: String,
     pub asset_url: String,
     pub asset_name: String,
     pub asset_size: u64,
+    #[serde(default)]
+    pub checksum_url: Option<String>,
+    #[serde(default)]
+    pub checksum_name: Option<String>,
+    #[serde(default)]
+    pub prerelease: bool,
+    /// Release body as published, in GitHub-flavoured markdown.
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub published_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DownloadProgress {
+    pub received: u64,
+    pub total: u64,
+    pub bytes_per_second: f64,
+    pub cancel_requested: Arc<AtomicBool>,
+}
+
+impl DownloadProgress {
+    fn new() -> Self {
+        Self {
+            received: 0,
+            total: 0,
+            bytes_per_second: 0.0,
+            cancel_requested: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn is_cancelling(&self) -> bool {
+        self.cancel_requested.load(Ordering::Relaxed)
+    }
+
+    pub fn percent(&self) -> Option<u8> {
+        if self.total == 0 {
+            return None;
+        }
+        Some(((self.received as f64 / self.total as f64) * 100.0).min(100.0) as u8)
+    }
+
+    pub fn eta(&self) -> Option<Duration> {
+        if self.total == 0 || self.bytes_per_second < 1.0 {
+            return None;
+        }
+        let remaining = self.total.saturating_sub(self.received);
+        Some(Duration::from_secs_f64(
+            remaining as f64 / self.bytes_per_second,
+        ))
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+pub const DEFAULT_RELEASES_URL: &str =
+    "https://api.github.com/repos/TrustTunnel/TrustTunnelClient/releases";
+
+/// Which releases are offered: stable ones only, or prereleases as well.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    Prerelease,
+}
+
+impl ReleaseChannel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Prerelease => "prerelease",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "stable" => Some(Self::Stable),
+            "prerelease" => Some(Self::Prerelease),
+            _ => None,
+        }
+    }
+}
+
+/// Where the client manager looks for releases. The URL may be any
+/// GitHub-compatible `/releases` endpoint or a JSON index, and plain `http://`
+/// works so a local stand-in server can be used for testing.
+#[derive(Clone, Debug, Default)]
+pub struct ReleaseSource {
+    pub releases_url: Option<String>,
+    pub github_token: Option<String>,
+    pub channel: ReleaseChannel,
+}
+
+impl ReleaseSource {
+    pub fn releases_url(&self) -> &str {
+        self.releases_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .unwrap_or(DEFAULT_RELEASES_URL)
+    }
+
+    /// The configured token, falling back to `GITHUB_TOKEN` for CI machines.
+    fn token(&self) -> Option<String> {
+        self.github_token
+            .clone()
+            .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty())
+    }
+}
+
+pub enum PinnedClient {
+    Installed(PathBuf),
+    Downloading(String),
+    Available(ClientRelease),
+    Unknown,
 }
 
 pub struct ClientManagerState {
     pub releases: Vec<ClientRelease>,
     pub installed: Vec<String>,
     pub selected_version: Option<String>,
-    pub downloads: HashMap<String, u8>,
+    pub downloads: HashMap<String, DownloadProgress>,
+    pub download_errors: HashMap<String, String>,
+    pub importing: bool,
+    pub import_error: Option<String>,
     pub fetching_releases: bool,
     pub releases_fetched: bool,
+    pub release_error: Option<String>,
+    pub fetch_retry_at: Option<Instant>,
+    pub releases_etag: Option<String>,
+    pub releases_fetched_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub release_source: ReleaseSource,
+    pub pending_upgrade: Option<String>,
+    pub prune_after_upgrade: bool,
+    /// Set when a background task changed `selected_version`, so the UI
+    /// knows to persist it.
+    pub selection_changed: bool,
+    /// How many installed versions to keep besides the selected one; `None`
+    /// keeps everything.
+    pub keep_versions: Option<usize>,
+    pub disk_usage: HashMap<String, u64>,
+    /// Binary of the session the UI is currently running, never cleaned up.
+    pub running_binary: Option<PathBuf>,
+    /// Probed capabilities by client binary; `None` while a probe runs.
+    pub capabilities: HashMap<PathBuf, Option<ClientCapabilities>>,
     pub http_client: Arc<dyn HttpClient>,
 }
 
@@ -35,12 +198,39 @@ impl ClientManagerState {
             installed: Vec::new(),
             selected_version: None,
             downloads: HashMap::new(),
+            download_errors: HashMap::new(),
+            importing: false,
+            import_error: None,
             fetching_releases: false,
             releases_fetched: false,
+            release_error: None,
+            fetch_retry_at: None,
+            releases_etag: None,
+            releases_fetched_at: None,
+            release_source: ReleaseSource::default(),
+            pending_upgrade: None,
+            prune_after_upgrade: false,
+            selection_changed: false,
+            keep_versions: None,
+            disk_usage: HashMap::new(),
+            running_binary: None,
+            capabilities: HashMap::new(),
             http_client,
         }
     }
 
+    pub fn refresh_installed(&mut self) {
+        let (installed, disk_usage) = scan_installed_with_usage();
+        self.set_installed(installed, disk_usage);
+    }
+
+    fn set_installed(&mut self, installed: Vec<String>, disk_usage: HashMap<String, u64>) {
+        self.installed = installed;
+        self.disk_usage = disk_usage;
+        self.capabilities
+            .retain(|binary_path, _| binary_path.exists());
+    }
+
     pub fn is_downloading(&self, tag: &str) -> bool {
         self.downloads.contains_key(tag)
     }
@@ -53,6 +243,37 @@ impl ClientManagerState {
         }
     }
 
+    /// The newest known release, when it is newer than the selected version.
+    pub fn available_update(&self) -> Option<&ClientRelease> {
+        let selected = self.selected_version.as_deref()?;
+        let newest = self
+            .releases
+            .iter()
+            .max_by_key(|release| version_sort_key(&release.tag))?;
+        (version_sort_key(&newest.tag) > version_sort_key(selected)).then_some(newest)
+    }
+
+    /// Where a credential pinned to `version` stands. Tags match with or
+    /// without the leading `v`.
+    pub fn pinned_client(&self, version: &str) -> PinnedClient {
+        let same_version =
+            |tag: &str| tag.trim_start_matches('v') == version.trim_start_matches('v');
+        if let Some(tag) = self.installed.iter().find(|tag| same_version(tag)) {
+            return PinnedClient::Installed(client_binary_path(tag));
+        }
+        if let Some(tag) = self.downloads.keys().find(|tag| same_version(tag)) {
+            return PinnedClient::Downloading(tag.clone());
+        }
+        match self
+            .releases
+            .iter()
+            .find(|release| same_version(&release.tag))
+        {
+            Some(release) => PinnedClient::Available(release.clone()),
+            None => PinnedClient::Unknown,
+        }
+    }
+
     pub fn selected_binary_path(&self) -> Option<PathBuf> {
         let version = self.selected_version.as_ref()?;
         if !self.installed.contains(version) {
@@ -77,54 +298,141 @@ pub fn clients_directory() -> PathBuf {
 }
 
 pub fn client_binary_path(version: &str) -> PathBuf {
-    let binary_name = if cfg!(target_os = "windows") {
-        "trusttunnel_client.exe"
-    } else {
-        "trusttunnel_client"
-    };
-    clients_directory().join(version).join(binary_name)
-}
-
-fn platform_asset_filter() -> (&'static str, &'static str) {
-    let operating_system = if cfg!(target_os = "windows") {
-        "windows"
-    } else if cfg!(target_os = "macos") {
-        "macos"
-    } else {
-        "linux"
-    };
-
-    let architecture = if cfg!(target_os = "macos") {
-        "universal"
-    } else if cfg!(target_arch = "x86_64") {
-        "x86_64"
-    } else if cfg!(target_arch = "aarch64") {
-        "aarch64"
-    } else if cfg!(target_arch = "arm") {
-        "armv7"
-    } else {
-        "x86_64"
-    };
-
-    (operating_system, architecture)
+    clients_directory().join(version).join(client_binary_name())
 }
 
+/// Picks the best-ranked asset for the detected target; ties go to the one
+/// listed first.
 fn find_platform_asset(assets: &[GitHubAsset]) -> Option<(String, String, u64)> {
-    let (operating_system, architecture) = platform_asset_filter();
-    let pattern = format!("-{operating_system}-{architecture}.");
-
-    for asset in assets {
-        if asset.name.contains(&pattern) {
-            return Some((
+    let target = client_target::current();
+    assets
+        .iter()
+        .filter_map(|asset| Some((target.asset_rank(&asset.name)?, asset)))
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, asset)| {
+            (
                 asset.name.clone(),
                 asset.browser_download_url.clone(),
                 asset.size,
-            ));
+            )
+        })
+}
+
+/// Release-wide checksum manifests in `sha256sum` format, tried when there is
+/// no `<asset>.sha256` next to the archive.
+const CHECKSUM_MANIFEST_NAMES: &[&str] = &[
+    "sha256sums",
+    "sha256sums.txt",
+    "checksums.txt",
+    "checksums.sha256",
+];
+
+fn find_checksum_asset<'a>(assets: &'a [GitHubAsset], asset_name: &str) -> Option<&'a GitHubAsset> {
+    let sidecar_names = [
+        format!("{asset_name}.sha256"),
+        format!("{asset_name}.sha256sum"),
+    ];
+    assets
+        .iter()
+        .find(|asset| sidecar_names.contains(&asset.name))
+        .or_else(|| {
+            assets.iter().find(|asset| {
+                CHECKSUM_MANIFEST_NAMES.contains(&asset.name.to_ascii_lowercase().as_str())
+            })
+        })
+}
+
+/// Picks the digest for `asset_name` out of a `sha256sum`-style listing. A
+/// line with a bare digest is accepted only when it is the sole entry, which
+/// is how per-asset `.sha256` files are usually written.
+fn parse_checksum(content: &str, asset_name: &str) -> Option<[u8; 32]> {
+    let lines: Vec<&str> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+
+    for line in &lines {
+        let mut parts = line.split_whitespace();
+        let Some(digest) = parts.next().and_then(decode_sha256) else {
+            continue;
+        };
+        match parts.next() {
+            Some(name) => {
+                let name = name.trim_start_matches('*');
+                let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+                if name == asset_name {
+                    return Some(digest);
+                }
+            }
+            None if lines.len() == 1 => return Some(digest),
+            None => {}
         }
     }
     None
 }
 
+fn decode_sha256(text: &str) -> Option<[u8; 32]> {
+    if text.len() != 64 || !text.is_ascii() {
+        return None;
+    }
+    let mut digest = [0u8; 32];
+    for (index, byte) in digest.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&text[index * 2..index * 2 + 2], 16).ok()?;
+    }
+    Some(digest)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn fetch_expected_checksum(
+    release: &ClientRelease,
+    http_client: &Arc<dyn HttpClient>,
+) -> Result<[u8; 32], String> {
+    let (Some(checksum_url), Some(checksum_name)) = (&release.checksum_url, &release.checksum_name)
+    else {
+        return Err(format!(
+            "Release {} publishes no SHA-256 checksum for {}; refusing to install",
+            release.tag, release.asset_name
+        ));
+    };
+
+    log::info!(
+        "[client_manager] fetching checksum {checksum_name} for {}",
+        release.tag
+    );
+
+    let request = http_client::Request::get(checksum_url)
+        .header("Accept", "application/octet-stream")
+        .follow_redirects(RedirectPolicy::FollowAll)
+        .body(AsyncBody::empty())
+        .map_err(|error| format!("Failed to build checksum request: {error}"))?;
+
+    let mut response = futures::executor::block_on(http_client.send(request))
+        .map_err(|error| format!("Checksum request failed: {error}"))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!(
+            "Checksum download failed with status {}",
+            status.as_u16()
+        ));
+    }
+
+    let mut body = Vec::new();
+    futures::executor::block_on(response.body_mut().take(1024 * 1024).read_to_end(&mut body))
+        .map_err(|error| format!("Failed to read checksum file: {error}"))?;
+
+    parse_checksum(&String::from_utf8_lossy(&body), &release.asset_name).ok_or_else(|| {
+        format!(
+            "{checksum_name} has no SHA-256 entry for {}",
+            release.asset_name
+        )
+    })
+}
+
 #[derive(Deserialize)]
 struct GitHubRelease {
     tag_name: String,
@@ -133,6 +441,10 @@ struct GitHubRelease {
     #[serde(default)]
     prerelease: bool,
     #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    published_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
     assets: Vec<GitHubAsset>,
 }
 
@@ -144,23 +456,338 @@ struct GitHubAsset {
     size: u64,
 }
 
+#[derive(Deserialize)]
+struct ReleaseIndex {
+    releases: Vec<IndexRelease>,
+}
+
+#[derive(Deserialize)]
+struct IndexRelease {
+    tag: String,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(default)]
+    published_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    assets: Vec<IndexAsset>,
+}
+
+#[derive(Deserialize)]
+struct IndexAsset {
+    name: String,
+    url: String,
+    #[serde(default)]
+    size: u64,
+}
+
+impl IndexRelease {
+    /// Index asset URLs may be relative to the index file itself, which keeps
+    /// a mirror directory relocatable.
+    fn into_github_release(self, index_url: &str) -> GitHubRelease {
+        let base = &index_url[..index_url.rfind('/').map_or(index_url.len(), |end| end + 1)];
+        GitHubRelease {
+            tag_name: self.tag,
+            draft: false,
+            prerelease: self.prerelease,
+            body: self.notes,
+            published_at: self.published_at,
+            assets: self
+                .assets
+                .into_iter()
+                .map(|asset| GitHubAsset {
+                    browser_download_url: if asset.url.contains("://") {
+                        asset.url
+                    } else {
+                        format!("{base}{}", asset.url.trim_start_matches("./"))
+                    },
+                    name: asset.name,
+                    size: asset.size,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Accepts either a GitHub `/releases` response or a `{"releases": [...]}` index.
+fn parse_release_listing(body: &[u8], url: &str) -> Result<Vec<GitHubRelease>, String> {
+    match serde_json::from_slice::<Vec<GitHubRelease>>(body) {
+        Ok(releases) => Ok(releases),
+        Err(github_error) => match serde_json::from_slice::<ReleaseIndex>(body) {
+            Ok(index) => Ok(index
+                .releases
+                .into_iter()
+                .map(|release| release.into_github_release(url))
+                .collect()),
+            Err(index_error) => Err(format!(
+                "Failed to parse releases JSON (GitHub format: {github_error}; index format: {index_error})"
+            )),
+        },
+    }
+}
+
+pub struct ReleaseFetchError {
+    pub message: String,
+    pub retry_after: Option<Duration>,
+}
+
+impl From<String> for ReleaseFetchError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            retry_after: None,
+        }
+    }
+}
+
+fn header_value<'a>(response: &'a http_client::Response<AsyncBody>, name: &str) -> Option<&'a str> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+}
+
+const MAX_RELEASE_REDIRECTS: usize = 5;
+
+/// Scheme and authority (`host[:port]`) of `url`, lowercased. Two URLs with
+/// the same origin may share a token.
+fn url_origin(url: &str) -> Option<(String, String)> {
+    let uri = url.parse::<http_client::Uri>().ok()?;
+    Some((
+        uri.scheme_str()?.to_ascii_lowercase(),
+        uri.authority()?.as_str().to_ascii_lowercase(),
+    ))
+}
+
+/// Resolves a `Location` header against the URL that returned it.
+fn resolve_redirect(current_url: &str, location: &str) -> Option<String> {
+    if location.contains("://") {
+        return Some(location.to_string());
+    }
+    let (scheme, authority) = url_origin(current_url)?;
+    if let Some(path) = location.strip_prefix("//") {
+        return Some(format!("{scheme}://{path}"));
+    }
+    if location.starts_with('/') {
+        return Some(format!("{scheme}://{authority}{location}"));
+    }
+    let base = current_url.split(['?', '#']).next()?;
+    let directory = &base[..base.rfind('/')? + 1];
+    Some(format!("{directory}{location}"))
+}
+
+/// Reads GitHub's `Retry-After` / `X-RateLimit-*` headers. Returns `None` when
+/// the response is not a rate-limit rejection.
+fn rate_limit_delay(response: &http_client::Response<AsyncBody>) -> Option<Duration> {
+    let status = response.status().as_u16();
+    let exhausted = header_value(response, "x-ratelimit-remaining") == Some("0");
+    if status != 429 && !(status == 403 && exhausted) {
+        return None;
+    }
+
+    if let Some(seconds) =
+        header_value(response, "retry-after").and_then(|value| value.parse::<u64>().ok())
+    {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let reset = header_value(response, "x-ratelimit-reset")
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(0);
+    let now = chrono::Utc::now().timestamp();
+    Some(Duration::from_secs(reset.saturating_sub(now).max(60) as u64))
+}
+
+fn format_wait(wait: Duration) -> String {
+    let seconds = wait.as_secs();
+    if seconds < 60 {
+        format!("{seconds} s")
+    } else {
+        format!("{} min", seconds.div_ceil(60))
+    }
+}
+
+/// Bumped whenever the cached `ClientRelease` contents change, e.g. new
+/// fields or a different asset choice, so an older cache is shown but fully
+/// refetched instead of being revalidated with its ETag. Format 2 holds
+/// assets picked by the detected target rather than the build target.
+const RELEASE_CACHE_FORMAT: u32 = 2;
+
+/// The release list as last fetched, kept so the Client tab has something to
+/// show before (or without) network access.
+#[derive(Serialize, Deserialize)]
+struct ReleaseCache {
+    #[serde(default)]
+    format: u32,
+    source_url: String,
+    #[serde(default)]
+    channel: ReleaseChannel,
+    #[serde(default)]
+    etag: Option<String>,
+    fetched_at: chrono::DateTime<chrono::Utc>,
+    releases: Vec<ClientRelease>,
+}
+
+fn release_cache_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("trusttunnel")
+        .join("releases-cache.json")
+}
+
+/// Seeds the state with the cached release list if it came from the
+/// currently configured source.
+pub fn load_release_cache(state: &mut ClientManagerState) {
+    let path = release_cache_path();
+    let Ok(content) = std::fs::read(&path) else {
+        return;
+    };
+    let cache: ReleaseCache = match serde_json::from_slice(&content) {
+        Ok(cache) => cache,
+        Err(error) => {
+            log::warn!(
+                "[client_manager] ignoring unreadable release cache {}: {error}",
+                path.display()
+            );
+            return;
+        }
+    };
+    if cache.source_url != state.release_source.releases_url()
+        || cache.channel != state.release_source.channel
+    {
+        log::info!("[client_manager] release cache is for another source, ignoring");
+        return;
+    }
+
+    log::info!(
+        "[client_manager] loaded {} cached releases fetched at {}",
+        cache.releases.len(),
+        cache.fetched_at
+    );
+    state.releases = cache.releases;
+    state.releases_etag = cache.etag.filter(|_| cache.format == RELEASE_CACHE_FORMAT);
+    state.releases_fetched_at = Some(cache.fetched_at);
+}
+
+fn save_release_cache(state: &ClientManagerState) {
+    let Some(fetched_at) = state.releases_fetched_at else {
+        return;
+    };
+    let cache = ReleaseCache {
+        format: RELEASE_CACHE_FORMAT,
+        source_url: state.release_source.releases_url().to_string(),
+        channel: state.release_source.channel,
+        etag: state.releases_etag.clone(),
+        fetched_at,
+        releases: state.releases.clone(),
+    };
+    let path = release_cache_path();
+    let result = serde_json::to_vec_pretty(&cache)
+        .map_err(|error| error.to_string())
+        .and_then(|content| std::fs::write(&path, content).map_err(|error| error.to_string()));
+    if let Err(error) = result {
+        log::warn!(
+            "[client_manager] failed to write release cache {}: {error}",
+            path.display()
+        );
+    }
+}
+
+pub enum ReleaseFetch {
+    NotModified,
+    Updated {
+        releases: Vec<ClientRelease>,
+        etag: Option<String>,
+    },
+}
+
 pub fn fetch_releases_blocking(
     http_client: &Arc<dyn HttpClient>,
-) -> Result<Vec<ClientRelease>, String> {
-    log::info!("[client_manager] fetching releases from GitHub");
+    source: &ReleaseSource,
+    etag: Option<&str>,
+) -> Result<ReleaseFetch, ReleaseFetchError> {
+    let url = source.releases_url();
+    log::info!("[client_manager] fetching releases from {url}");
+
+    let mut token = source.token();
+    if token.is_some() && url_origin(url).is_none_or(|(scheme, _)| scheme != "https") {
+        log::warn!("[client_manager] not sending the GitHub token to non-HTTPS {url}");
+        token = None;
+    }
+    let sent_token = token.is_some();
+
+    // With a token attached, redirects are followed by hand so the token
+    // only ever goes to the origin it was configured for.
+    let mut request_url = url.to_string();
+    let mut redirects = 0;
+    let mut response = loop {
+        let mut request = http_client::Request::get(&request_url)
+            .header("Accept", "application/vnd.github+json")
+            .follow_redirects(if token.is_some() {
+                RedirectPolicy::NoFollow
+            } else {
+                RedirectPolicy::FollowAll
+            });
+        if let Some(ref token) = token {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        if let Some(etag) = etag {
+            request = request.header("If-None-Match", etag);
+        }
+        let request = request
+            .body(AsyncBody::empty())
+            .map_err(|error| format!("Failed to build request: {error}"))?;
 
-    let url = "https://api.github.com/repos/TrustTunnel/TrustTunnelClient/releases";
+        let response = futures::executor::block_on(http_client.send(request))
+            .map_err(|error| format!("HTTP request failed: {error}"))?;
 
-    let request = http_client::Request::get(url)
-        .header("Accept", "application/vnd.github+json")
-        .follow_redirects(RedirectPolicy::FollowAll)
-        .body(AsyncBody::empty())
-        .map_err(|error| format!("Failed to build request: {error}"))?;
+        if token.is_none() || !response.status().is_redirection() {
+            break response;
+        }
+        let Some(location) = header_value(&response, "location") else {
+            break response;
+        };
+        redirects += 1;
+        if redirects > MAX_RELEASE_REDIRECTS {
+            return Err("Release source redirected too many times"
+                .to_string()
+                .into());
+        }
+        let next_url = resolve_redirect(&request_url, location)
+            .ok_or_else(|| format!("Release source sent an invalid redirect to {location}"))?;
+        if url_origin(&next_url) != url_origin(url) {
+            log::info!(
+                "[client_manager] release source redirected to another host, dropping the token"
+            );
+            token = None;
+        }
+        request_url = next_url;
+    };
 
-    let mut response = futures::executor::block_on(http_client.send(request))
-        .map_err(|error| format!("HTTP request failed: {error}"))?;
+    if let Some(wait) = rate_limit_delay(&response) {
+        let hint = if sent_token {
+            ""
+        } else {
+            "; add a GitHub token under Release Source in the Client tab to raise the limit"
+        };
+        return Err(ReleaseFetchError {
+            message: format!(
+                "Release source rate limit reached, retry in {}{hint}",
+                format_wait(wait)
+            ),
+            retry_after: Some(wait),
+        });
+    }
 
     let status = response.status();
+    if status.as_u16() == 304 {
+        log::info!("[client_manager] cached release list is still current");
+        return Ok(ReleaseFetch::NotModified);
+    }
+    let new_etag = header_value(&response, "etag").map(str::to_string);
+
     let mut body = Vec::new();
     futures::executor::block_on(response.body_mut().read_to_end(&mut body))
         .map_err(|error| format!("Failed to read response body: {error}"))?;
@@ -168,26 +795,32 @@ pub fn fetch_releases_blocking(
     if !status.is_success() {
         let text = String::from_utf8_lossy(&body);
         return Err(format!(
-            "GitHub API returned status {}: {}",
+            "Release source returned status {}: {}",
             status.as_u16(),
             text.chars().take(200).collect::<String>()
-        ));
+        )
+        .into());
     }
 
-    let github_releases: Vec<GitHubRelease> = serde_json::from_slice(&body)
-        .map_err(|error| format!("Failed to parse releases JSON: {error}"))?;
+    let github_releases = parse_release_listing(&body, url)?;
 
     let mut releases = Vec::new();
     for release in &github_releases {
-        if release.draft || release.prerelease {
+        if release.draft || (release.prerelease && source.channel != ReleaseChannel::Prerelease) {
             continue;
         }
         if let Some((asset_name, asset_url, asset_size)) = find_platform_asset(&release.assets) {
+            let checksum_asset = find_checksum_asset(&release.assets, &asset_name);
             releases.push(ClientRelease {
                 tag: release.tag_name.clone(),
                 asset_url,
                 asset_name,
                 asset_size,
+                checksum_url: checksum_asset.map(|asset| asset.browser_download_url.clone()),
+                checksum_name: checksum_asset.map(|asset| asset.name.clone()),
+                prerelease: release.prerelease,
+                notes: release.body.clone().unwrap_or_default(),
+                published_at: release.published_at,
             });
         }
     }
@@ -196,98 +829,258 @@ pub fn fetch_releases_blocking(
         "[client_manager] found {} releases with platform assets",
         releases.len()
     );
-    Ok(releases)
+    Ok(ReleaseFetch::Updated {
+        releases,
+        etag: new_etag,
+    })
 }
 
-pub fn download_release_blocking(
+const MAX_RESUME_ATTEMPTS: u32 = 3;
+const SPEED_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+enum DownloadAttemptError {
+    Cancelled,
+    /// Connection-level trouble; retrying with a `Range` request may succeed.
+    Interrupted(String),
+    Failed(String),
+}
+
+/// Fetches the release asset into `part_path`, continuing from whatever bytes
+/// an earlier attempt already left there.
+fn download_attempt_blocking(
     release: &ClientRelease,
+    part_path: &Path,
     state: &Arc<Mutex<ClientManagerState>>,
     http_client: &Arc<dyn HttpClient>,
-) -> Result<(), String> {
+    cancel_requested: &AtomicBool,
+) -> Result<(), DownloadAttemptError> {
     let version = &release.tag;
-    let version_directory = clients_directory().join(version);
+    let offset = std::fs::metadata(part_path)
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
 
-    if let Err(error) = std::fs::create_dir_all(&version_directory) {
-        return Err(format!(
-            "Failed to create directory {}: {error}",
-            version_directory.display()
-        ));
+    if release.asset_size > 0 && offset == release.asset_size {
+        return Ok(());
     }
 
-    let archive_path = version_directory.join(&release.asset_name);
-
-    log::info!(
-        "[client_manager] downloading {} → {} (expected {} bytes)",
-        release.asset_url,
-        archive_path.display(),
-        release.asset_size,
-    );
-
-    let request = http_client::Request::get(&release.asset_url)
+    let mut request = http_client::Request::get(&release.asset_url)
         .header("Accept", "application/octet-stream")
-        .follow_redirects(RedirectPolicy::FollowAll)
-        .body(AsyncBody::empty())
-        .map_err(|error| format!("Failed to build download request: {error}"))?;
+        .follow_redirects(RedirectPolicy::FollowAll);
+    if offset > 0 {
+        log::info!("[client_manager] resuming {version} from byte {offset}");
+        request = request.header("Range", format!("bytes={offset}-"));
+    }
+    let request = request.body(AsyncBody::empty()).map_err(|error| {
+        DownloadAttemptError::Failed(format!("Failed to build download request: {error}"))
+    })?;
 
-    let mut response = futures::executor::block_on(http_client.send(request))
-        .map_err(|error| format!("Download request failed: {error}"))?;
+    let mut response = futures::executor::block_on(http_client.send(request)).map_err(|error| {
+        DownloadAttemptError::Interrupted(format!("Download request failed: {error}"))
+    })?;
 
     let status = response.status();
+    let resumed = status.as_u16() == 206
+        && response
+            .headers()
+            .get("content-range")
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with(&format!("bytes {offset}-")));
+
+    if offset > 0 && !resumed {
+        // The server ignored or rejected the range; start over from scratch.
+        let _ = std::fs::remove_file(part_path);
+        if status.as_u16() == 206 || status.as_u16() == 416 {
+            return Err(DownloadAttemptError::Interrupted(format!(
+                "Server rejected resume at byte {offset}"
+            )));
+        }
+    }
     if !status.is_success() {
-        return Err(format!("Download failed with status {}", status.as_u16()));
+        let message = format!("Download failed with status {}", status.as_u16());
+        return Err(if status.is_server_error() {
+            DownloadAttemptError::Interrupted(message)
+        } else {
+            DownloadAttemptError::Failed(message)
+        });
     }
 
-    let expected = release.asset_size;
+    let mut received = if resumed { offset } else { 0 };
+    let total = if release.asset_size > 0 {
+        release.asset_size
+    } else {
+        response
+            .headers()
+            .get("content-length")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+            .map(|length| length + received)
+            .unwrap_or(0)
+    };
 
-    let download_result: Result<(), String> = futures::executor::block_on(async {
-        let mut file = std::fs::File::create(&archive_path)
-            .map_err(|error| format!("Failed to create archive file: {error}"))?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part_path)
+        .map_err(|error| {
+            DownloadAttemptError::Failed(format!("Failed to open archive file: {error}"))
+        })?;
 
+    futures::executor::block_on(async {
         let body = response.body_mut();
-        let mut received: u64 = 0;
         let mut buffer = vec![0u8; 32768];
+        let mut sample_started = Instant::now();
+        let mut sample_bytes: u64 = 0;
+        let mut bytes_per_second = 0.0;
+
+        update_download_progress(state, version, received, total, bytes_per_second);
 
         loop {
-            let bytes_read = body
-                .read(&mut buffer)
-                .await
-                .map_err(|error| format!("Failed to read response body: {error}"))?;
+            if cancel_requested.load(Ordering::Relaxed) {
+                return Err(DownloadAttemptError::Cancelled);
+            }
+
+            let bytes_read = body.read(&mut buffer).await.map_err(|error| {
+                DownloadAttemptError::Interrupted(format!("Failed to read response body: {error}"))
+            })?;
 
             if bytes_read == 0 {
                 break;
             }
 
-            file.write_all(&buffer[..bytes_read])
-                .map_err(|error| format!("Failed to write to archive file: {error}"))?;
+            file.write_all(&buffer[..bytes_read]).map_err(|error| {
+                DownloadAttemptError::Failed(format!("Failed to write to archive file: {error}"))
+            })?;
 
             received += bytes_read as u64;
+            sample_bytes += bytes_read as u64;
 
-            if expected > 0 {
-                let percent = ((received as f64 / expected as f64) * 100.0).min(99.0) as u8;
-                if let Ok(mut locked) = state.lock() {
-                    locked.downloads.insert(version.to_string(), percent);
-                }
+            let elapsed = sample_started.elapsed();
+            if elapsed >= SPEED_SAMPLE_INTERVAL {
+                bytes_per_second = sample_bytes as f64 / elapsed.as_secs_f64();
+                sample_started = Instant::now();
+                sample_bytes = 0;
             }
+            update_download_progress(state, version, received, total, bytes_per_second);
         }
 
-        file.flush()
-            .map_err(|error| format!("Failed to flush archive file: {error}"))?;
+        file.flush().map_err(|error| {
+            DownloadAttemptError::Failed(format!("Failed to flush archive file: {error}"))
+        })?;
 
+        if total > 0 && received < total {
+            return Err(DownloadAttemptError::Interrupted(format!(
+                "Connection closed after {received} of {total} bytes"
+            )));
+        }
         Ok(())
-    });
+    })
+}
 
-    if let Err(error) = download_result {
-        let _ = std::fs::remove_file(&archive_path);
-        return Err(error);
+fn update_download_progress(
+    state: &Arc<Mutex<ClientManagerState>>,
+    version: &str,
+    received: u64,
+    total: u64,
+    bytes_per_second: f64,
+) {
+    if let Ok(mut locked) = state.lock()
+        && let Some(progress) = locked.downloads.get_mut(version)
+    {
+        progress.received = received;
+        progress.total = total;
+        progress.bytes_per_second = bytes_per_second;
     }
+}
 
-    if let Ok(mut locked) = state.lock() {
-        locked.downloads.insert(version.to_string(), 100);
+fn sha256_file(path: &Path) -> Result<[u8; 32], String> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|error| format!("Failed to open archive for hashing: {error}"))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .map_err(|error| format!("Failed to hash archive: {error}"))?;
+    Ok(hasher.finalize().into())
+}
+
+pub fn download_release_blocking(
+    release: &ClientRelease,
+    state: &Arc<Mutex<ClientManagerState>>,
+    http_client: &Arc<dyn HttpClient>,
+    cancel_requested: &AtomicBool,
+) -> Result<(), String> {
+    let version = &release.tag;
+    let version_directory = clients_directory().join(version);
+
+    let expected_checksum = fetch_expected_checksum(release, http_client)?;
+
+    if let Err(error) = std::fs::create_dir_all(&version_directory) {
+        return Err(format!(
+            "Failed to create directory {}: {error}",
+            version_directory.display()
+        ));
+    }
+
+    let archive_path = version_directory.join(&release.asset_name);
+    let part_path = version_directory.join(format!("{}.part", release.asset_name));
+
+    log::info!(
+        "[client_manager] downloading {} → {} (expected {} bytes)",
+        release.asset_url,
+        part_path.display(),
+        release.asset_size,
+    );
+
+    let mut attempt = 0;
+    loop {
+        if cancel_requested.load(Ordering::Relaxed) {
+            let _ = std::fs::remove_dir_all(&version_directory);
+            return Err("Download cancelled".into());
+        }
+        match download_attempt_blocking(release, &part_path, state, http_client, cancel_requested) {
+            Ok(()) => break,
+            Err(DownloadAttemptError::Interrupted(error)) if attempt < MAX_RESUME_ATTEMPTS => {
+                attempt += 1;
+                log::warn!(
+                    "[client_manager] download of {version} interrupted ({error}), resuming (attempt {attempt}/{MAX_RESUME_ATTEMPTS})"
+                );
+                std::thread::sleep(Duration::from_secs(attempt as u64));
+            }
+            Err(DownloadAttemptError::Cancelled) => {
+                let _ = std::fs::remove_dir_all(&version_directory);
+                return Err("Download cancelled".into());
+            }
+            // The partial file stays on disk so the next attempt resumes from it.
+            Err(DownloadAttemptError::Interrupted(error) | DownloadAttemptError::Failed(error)) => {
+                return Err(error);
+            }
+        }
     }
 
-    if !archive_path.exists() {
-        return Err("Download completed but archive file not found".into());
+    std::fs::rename(&part_path, &archive_path)
+        .map_err(|error| format!("Failed to finalize archive file: {error}"))?;
+
+    let actual_checksum = match sha256_file(&archive_path) {
+        Ok(checksum) => checksum,
+        Err(error) => {
+            let _ = std::fs::remove_file(&archive_path);
+            return Err(error);
+        }
+    };
+
+    if actual_checksum != expected_checksum {
+        let _ = std::fs::remove_dir_all(&version_directory);
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}; refusing to install",
+            release.asset_name,
+            encode_hex(&expected_checksum),
+            encode_hex(&actual_checksum)
+        ));
     }
+    log::info!(
+        "[client_manager] verified SHA-256 of {}",
+        release.asset_name
+    );
 
     log::info!(
         "[client_manager] extracting {} in {}",
@@ -295,43 +1088,226 @@ pub fn download_release_blocking(
         version_directory.display()
     );
 
-    extract_archive(&archive_path, &version_directory)?;
+    if let Err(error) = extract_archive(&archive_path, &version_directory) {
+        let _ = std::fs::remove_dir_all(&version_directory);
+        return Err(error);
+    }
     let _ = std::fs::remove_file(&archive_path);
 
     let binary_path = client_binary_path(version);
     if !binary_path.exists() {
-        if let Some(found) = find_binary_recursive(&version_directory) {
-            if found != binary_path {
-                if let Err(error) = std::fs::rename(&found, &binary_path) {
-                    return Err(format!(
-                        "Found binary at {} but failed to move: {error}",
-                        found.display()
-                    ));
-                }
-                cleanup_empty_directories(&version_directory, &binary_path);
-            }
-        } else {
-            let _ = std::fs::remove_dir_all(&version_directory);
-            return Err("Extraction completed but client binary not found in archive".into());
-        }
+        let _ = std::fs::remove_dir_all(&version_directory);
+        return Err("Extraction completed but client binary not found in archive".into());
     }
+    if let Err(error) = client_target::check_binary(client_target::current(), &binary_path) {
+        let _ = std::fs::remove_dir_all(&version_directory);
+        return Err(format!("{error}; refusing to install"));
+    }
+
+    make_executable(&binary_path);
+
+    log::info!(
+        "[client_manager] successfully installed {} at {}",
+        version,
+        binary_path.display()
+    );
+    Ok(())
+}
 
+fn make_executable(binary_path: &Path) {
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
         if let Err(error) =
-            std::fs::set_permissions(&binary_path, std::fs::Permissions::from_mode(0o755))
+            std::fs::set_permissions(binary_path, std::fs::Permissions::from_mode(0o755))
         {
             log::warn!("[client_manager] failed to set executable permission: {error}");
         }
     }
+    #[cfg(not(unix))]
+    let _ = binary_path;
+}
+
+const VERSION_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Runs `<binary> <argument>` with a timeout and returns stdout and stderr
+/// combined, whatever the exit status.
+pub fn run_client_probe(binary_path: &Path, argument: &str) -> Result<String, String> {
+    let mut command = std::process::Command::new(binary_path);
+    command
+        .arg(argument)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x08000000);
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|error| format!("Failed to run {}: {error}", binary_path.display()))?;
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() > VERSION_PROBE_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "{} {argument} did not finish in {} seconds",
+                    binary_path.display(),
+                    VERSION_PROBE_TIMEOUT.as_secs()
+                ));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            Err(error) => return Err(format!("Failed to wait for {argument}: {error}")),
+        }
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|error| format!("Failed to read {argument} output: {error}"))?;
+    Ok(format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    ))
+}
+
+/// Runs `<binary> --version` and turns the first dotted number it prints into
+/// a `v`-prefixed tag matching the GitHub release names.
+pub fn detect_client_version(binary_path: &Path) -> Result<String, String> {
+    let text = run_client_probe(binary_path, "--version")?;
+    parse_version_output(&text)
+        .map(|version| format!("v{version}"))
+        .ok_or_else(|| {
+            format!(
+                "No version number in --version output: {}",
+                text.trim().lines().next().unwrap_or_default()
+            )
+        })
+}
+
+fn parse_version_output(text: &str) -> Option<String> {
+    text.split(|character: char| character.is_whitespace() || ",;()[]".contains(character))
+        .map(|token| {
+            let token = token.trim_start_matches(['v', 'V']);
+            // Keep a pre-release suffix such as `-beta.1`, which release tags
+            // carry too, but drop build metadata such as `+abc123`.
+            token.split('+').next().unwrap_or(token)
+        })
+        .find(|token| {
+            let core = token.split('-').next().unwrap_or(token);
+            let parts: Vec<&str> = core.split('.').collect();
+            parts.len() >= 2
+                && parts
+                    .iter()
+                    .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+                && token
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        })
+        .map(str::to_string)
+}
+
+fn is_archive(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    name.ends_with(".zip") || name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// Puts the client from a local archive or binary into `staging_directory`,
+/// laid out exactly like a downloaded release.
+fn stage_local_client(source: &Path, staging_directory: &Path) -> Result<(), String> {
+    if is_archive(source) {
+        extract_archive(source, staging_directory)?;
+    } else {
+        std::fs::copy(source, staging_directory.join(client_binary_name()))
+            .map_err(|error| format!("Failed to copy {}: {error}", source.display()))?;
+
+        // Pick up shared libraries that sit next to the binary.
+        if let Some(entries) = source
+            .parent()
+            .and_then(|directory| std::fs::read_dir(directory).ok())
+        {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                if path.is_file() && name != client_binary_name() && is_client_file(name) {
+                    std::fs::copy(&path, staging_directory.join(name))
+                        .map_err(|error| format!("Failed to copy {name}: {error}"))?;
+                }
+            }
+        }
+    }
+
+    let binary_path = staging_directory.join(client_binary_name());
+    if !binary_path.exists() {
+        return Err(format!(
+            "No {} found in {}",
+            client_binary_name(),
+            source.display()
+        ));
+    }
+    client_target::check_binary(client_target::current(), &binary_path)?;
+    make_executable(&binary_path);
+    Ok(())
+}
+
+/// Installs a client from a local `.tar.gz`/`.zip` or an existing binary and
+/// returns the version it registered under.
+pub fn install_local_blocking(
+    source: &Path,
+    state: &Arc<Mutex<ClientManagerState>>,
+) -> Result<String, String> {
+    let staging_directory = clients_directory().join(format!(".import-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&staging_directory);
+    std::fs::create_dir_all(&staging_directory).map_err(|error| {
+        format!(
+            "Failed to create directory {}: {error}",
+            staging_directory.display()
+        )
+    })?;
 
     log::info!(
-        "[client_manager] successfully installed {} at {}",
-        version,
-        binary_path.display()
+        "[client_manager] installing local client from {}",
+        source.display()
     );
-    Ok(())
+
+    let result = stage_local_client(source, &staging_directory).and_then(|()| {
+        let version = detect_client_version(&staging_directory.join(client_binary_name()))?;
+        if client_binary_path(&version).exists() {
+            return Err(format!("Client {version} is already installed"));
+        }
+        if state
+            .lock()
+            .is_ok_and(|locked| locked.is_downloading(&version))
+        {
+            return Err(format!("Client {version} is currently being downloaded"));
+        }
+
+        let version_directory = clients_directory().join(&version);
+        let _ = std::fs::remove_dir_all(&version_directory);
+        std::fs::rename(&staging_directory, &version_directory).map_err(|error| {
+            format!(
+                "Failed to move client into {}: {error}",
+                version_directory.display()
+            )
+        })?;
+        Ok(version)
+    });
+
+    if result.is_err() {
+        let _ = std::fs::remove_dir_all(&staging_directory);
+    }
+    result
 }
 
 pub fn remove_client(version: &str) -> Result<(), String> {
@@ -345,6 +1321,22 @@ pub fn remove_client(version: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Rescans the installed clients without holding the lock during the disk I/O.
+pub fn rescan_installed(state: &Arc<Mutex<ClientManagerState>>) {
+    let (installed, disk_usage) = scan_installed_with_usage();
+    state.lock().unwrap().set_installed(installed, disk_usage);
+}
+
+fn scan_installed_with_usage() -> (Vec<String>, HashMap<String, u64>) {
+    let installed = scan_installed_clients();
+    let directory = clients_directory();
+    let disk_usage = installed
+        .iter()
+        .map(|version| (version.clone(), directory_size(&directory.join(version))))
+        .collect();
+    (installed, disk_usage)
+}
+
 pub fn scan_installed_clients() -> Vec<String> {
     let directory = clients_directory();
     let mut installed = Vec::new();
@@ -360,7 +1352,7 @@ pub fn scan_installed_clients() -> Vec<String> {
             continue;
         }
         if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-            if !name.starts_with('v') {
+            if name.starts_with('.') {
                 continue;
             }
             let binary = client_binary_path(name);
@@ -378,202 +1370,763 @@ pub fn scan_installed_clients() -> Vec<String> {
     installed
 }
 
+/// Points the client manager at another release URL and token. The list is
+/// fetched again, from scratch when the URL changed.
+pub fn set_release_source(
+    state: Arc<Mutex<ClientManagerState>>,
+    releases_url: Option<String>,
+    github_token: Option<String>,
+) {
+    {
+        let mut locked = state.lock().unwrap();
+        let previous_url = locked.release_source.releases_url().to_string();
+        locked.release_source.releases_url = releases_url;
+        locked.release_source.github_token = github_token;
+        if locked.release_source.releases_url() != previous_url {
+            log::info!(
+                "[client_manager] switching release source to {}",
+                locked.release_source.releases_url()
+            );
+            locked.releases.clear();
+            locked.releases_etag = None;
+            locked.releases_fetched_at = None;
+        }
+        locked.releases_fetched = false;
+        locked.release_error = None;
+        locked.fetch_retry_at = None;
+    }
+    start_fetch_releases(state);
+}
+
+/// Switches the release channel and fetches the list again from scratch.
+pub fn set_release_channel(state: Arc<Mutex<ClientManagerState>>, channel: ReleaseChannel) {
+    {
+        let mut locked = state.lock().unwrap();
+        if locked.release_source.channel == channel {
+            return;
+        }
+        log::info!("[client_manager] switching to {} channel", channel.as_str());
+        locked.release_source.channel = channel;
+        locked.releases.clear();
+        locked.releases_etag = None;
+        locked.releases_fetched_at = None;
+        locked.releases_fetched = false;
+        locked.fetch_retry_at = None;
+    }
+    start_fetch_releases(state);
+}
+
 pub fn start_fetch_releases(state: Arc<Mutex<ClientManagerState>>) {
     let http_client;
+    let source;
+    let etag;
     {
         let mut locked = state.lock().unwrap();
-        if locked.releases_fetched || locked.fetching_releases {
+        if locked.fetching_releases {
+            return;
+        }
+        // A failed fetch is retried on the next request, but not before the
+        // release source said it would accept one again.
+        let retry_allowed = locked.release_error.is_some()
+            && locked
+                .fetch_retry_at
+                .is_none_or(|retry_at| Instant::now() >= retry_at);
+        if locked.releases_fetched && !retry_allowed {
             return;
         }
         locked.fetching_releases = true;
         http_client = locked.http_client.clone();
+        source = locked.release_source.clone();
+        // Revalidate only when there is a cached list to fall back on.
+        etag = locked.releases_fetched_at.and(locked.releases_etag.clone());
     }
 
     std::thread::spawn(move || {
-        let result = fetch_releases_blocking(&http_client);
+        let result = fetch_releases_blocking(&http_client, &source, etag.as_deref());
         let mut locked = state.lock().unwrap();
         locked.fetching_releases = false;
+        if locked.release_source.channel != source.channel {
+            // The channel changed while this fetch was in flight; start over.
+            drop(locked);
+            start_fetch_releases(state);
+            return;
+        }
+        locked.releases_fetched = true;
         match result {
-            Ok(releases) => {
-                locked.releases = releases;
-                locked.releases_fetched = true;
+            Ok(fetch) => {
+                if let ReleaseFetch::Updated { releases, etag } = fetch {
+                    locked.releases = releases;
+                    locked.releases_etag = etag;
+                }
+                locked.releases_fetched_at = Some(chrono::Utc::now());
+                locked.release_error = None;
+                locked.fetch_retry_at = None;
+                save_release_cache(&locked);
                 log::info!(
                     "[client_manager] fetched {} releases",
                     locked.releases.len()
                 );
             }
             Err(error) => {
-                locked.releases_fetched = true;
-                log::error!("[client_manager] fetch releases failed: {error}");
+                log::error!("[client_manager] fetch releases failed: {}", error.message);
+                locked.fetch_retry_at = error.retry_after.map(|wait| Instant::now() + wait);
+                locked.release_error = Some(error.message);
             }
         }
     });
 }
 
+/// The version tag of a binary inside `clients/`, if it is a managed one.
+fn managed_version(binary_path: &Path) -> Option<String> {
+    let version_directory = binary_path.parent()?;
+    if version_directory.parent()? != clients_directory() {
+        return None;
+    }
+    version_directory
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_string)
+}
+
+/// Returns what `binary_path` is known to support, starting a background
+/// probe the first time it is asked about.
+pub fn capabilities_for(
+    state: &Arc<Mutex<ClientManagerState>>,
+    binary_path: &Path,
+) -> Option<ClientCapabilities> {
+    {
+        let mut locked = state.lock().unwrap();
+        if let Some(capabilities) = locked.capabilities.get(binary_path) {
+            return capabilities.clone();
+        }
+        locked.capabilities.insert(binary_path.to_path_buf(), None);
+    }
+
+    let state = state.clone();
+    let binary_path = binary_path.to_path_buf();
+    std::thread::spawn(move || {
+        let capabilities =
+            client_capabilities::probe(&binary_path, managed_version(&binary_path).as_deref());
+        if let Ok(mut locked) = state.lock() {
+            locked.capabilities.insert(binary_path, Some(capabilities));
+        }
+    });
+    None
+}
+
+/// What `binary_path` is known to support, without starting a probe.
+pub fn cached_capabilities(
+    state: &Arc<Mutex<ClientManagerState>>,
+    binary_path: &Path,
+) -> Option<ClientCapabilities> {
+    state
+        .lock()
+        .unwrap()
+        .capabilities
+        .get(binary_path)
+        .cloned()
+        .flatten()
+}
+
+/// Like [`capabilities_for`], but waits for the probe; run off the UI thread
+/// right before spawning the client.
+pub fn probe_capabilities_blocking(
+    state: &Arc<Mutex<ClientManagerState>>,
+    binary_path: &Path,
+) -> ClientCapabilities {
+    if let Some(Some(capabilities)) = state.lock().unwrap().capabilities.get(binary_path) {
+        return capabilities.clone();
+    }
+    let capabilities =
+        client_capabilities::probe(binary_path, managed_version(binary_path).as_deref());
+    if let Ok(mut locked) = state.lock() {
+        locked
+            .capabilities
+            .insert(binary_path.to_path_buf(), Some(capabilities.clone()));
+    }
+    capabilities
+}
+
+/// Probes a client outside of the UI, for the command line.
+pub fn probe_capabilities(binary_path: &Path) -> ClientCapabilities {
+    client_capabilities::probe(binary_path, managed_version(binary_path).as_deref())
+}
+
+pub fn start_local_install(state: Arc<Mutex<ClientManagerState>>, source: PathBuf) {
+    {
+        let mut locked = state.lock().unwrap();
+        if locked.importing {
+            return;
+        }
+        locked.importing = true;
+        locked.import_error = None;
+    }
+
+    std::thread::spawn(move || {
+        let result = install_local_blocking(&source, &state);
+        if result.is_ok() {
+            rescan_installed(&state);
+        }
+
+        let mut locked = state.lock().unwrap();
+        locked.importing = false;
+        match result {
+            Ok(version) => {
+                if !locked.has_selected_client() {
+                    locked.selected_version = Some(version.clone());
+                    locked.selection_changed = true;
+                    log::info!("[client_manager] auto-selected version {version}");
+                }
+
+                log::info!("[client_manager] local install complete: {version}");
+            }
+            Err(error) => {
+                log::error!(
+                    "[client_manager] local install from {} failed: {error}",
+                    source.display()
+                );
+                locked.import_error = Some(error);
+            }
+        }
+    });
+}
+
+pub fn cancel_download(state: &Arc<Mutex<ClientManagerState>>, version: &str) {
+    if let Ok(locked) = state.lock()
+        && let Some(progress) = locked.downloads.get(version)
+    {
+        log::info!("[client_manager] cancelling download of {version}");
+        progress.cancel_requested.store(true, Ordering::Relaxed);
+    }
+}
+
 pub fn start_download(state: Arc<Mutex<ClientManagerState>>, release: ClientRelease) {
     let version = release.tag.clone();
 
     let http_client;
+    let cancel_requested;
     {
         let mut locked = state.lock().unwrap();
         if locked.is_downloading(&version) {
             return;
         }
-        locked.downloads.insert(version.clone(), 0);
+        let progress = DownloadProgress::new();
+        cancel_requested = progress.cancel_requested.clone();
+        locked.downloads.insert(version.clone(), progress);
+        locked.download_errors.remove(&version);
         http_client = locked.http_client.clone();
     }
 
     std::thread::spawn(move || {
-        let download_result = download_release_blocking(&release, &state, &http_client);
+        let download_result =
+            download_release_blocking(&release, &state, &http_client, &cancel_requested);
+        if download_result.is_ok() {
+            rescan_installed(&state);
+        }
 
         let mut locked = state.lock().unwrap();
         locked.downloads.remove(&version);
+        let upgrading = locked.pending_upgrade.as_deref() == Some(version.as_str());
         match download_result {
+            Err(_) if cancel_requested.load(Ordering::Relaxed) => {
+                log::info!("[client_manager] download cancelled: {version}");
+            }
             Ok(()) => {
-                locked.installed = scan_installed_clients();
-
-                if !locked.has_selected_client() {
-                    locked.selected_version = Some(version.clone());
-                    log::info!("[client_manager] auto-selected version {version}");
-                }
-
                 log::info!("[client_manager] download complete: {version}");
+                if upgrading {
+                    drop(locked);
+                    finish_upgrade(&state, &version);
+                } else {
+                    if !locked.has_selected_client() {
+                        locked.selected_version = Some(version.clone());
+                        locked.selection_changed = true;
+                        log::info!("[client_manager] auto-selected version {version}");
+                    }
+                    drop(locked);
+                }
+                apply_retention(&state);
+                return;
             }
             Err(error) => {
                 log::error!("[client_manager] download failed for {version}: {error}");
+                locked.download_errors.insert(version.clone(), error);
             }
         }
+        if upgrading {
+            locked.pending_upgrade = None;
+        }
     });
 }
 
-#[cfg(not(target_os = "windows"))]
-fn extract_archive(
-    archive_path: &std::path::Path,
-    target_directory: &std::path::Path,
-) -> Result<(), String> {
-    let output = std::process::Command::new("tar")
-        .args(["xzf"])
-        .arg(archive_path)
-        .arg("-C")
-        .arg(target_directory)
-        .stdin(std::process::Stdio::null())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .output()
-        .map_err(|error| format!("Failed to run tar: {error}"))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!(
-            "tar extraction failed (exit {}): {}",
-            output.status.code().unwrap_or(-1),
-            stderr.trim()
-        ));
+/// Switches to `release`, downloading it first when it is not installed yet.
+pub fn start_upgrade(state: Arc<Mutex<ClientManagerState>>, release: ClientRelease) {
+    {
+        let mut locked = state.lock().unwrap();
+        if locked.pending_upgrade.is_some() {
+            return;
+        }
+        if locked.installed.contains(&release.tag) {
+            locked.pending_upgrade = Some(release.tag.clone());
+            drop(locked);
+            std::thread::spawn(move || finish_upgrade(&state, &release.tag));
+            return;
+        }
+        log::info!("[client_manager] upgrading to {}", release.tag);
+        locked.pending_upgrade = Some(release.tag.clone());
     }
+    start_download(state, release);
+}
 
-    Ok(())
+fn finish_upgrade(state: &Arc<Mutex<ClientManagerState>>, version: &str) {
+    let pinned = pinned_client_versions(&credentials_directory());
+    let (outdated, running_binary) = {
+        let mut locked = state.lock().unwrap();
+        locked.pending_upgrade = None;
+        locked.selected_version = Some(version.to_string());
+        locked.selection_changed = true;
+        log::info!("[client_manager] upgraded to {version}");
+
+        if !locked.prune_after_upgrade {
+            return;
+        }
+        let upgraded_key = version_sort_key(version);
+        let outdated: Vec<String> = locked
+            .installed
+            .iter()
+            .filter(|old_version| {
+                version_sort_key(old_version) < upgraded_key && !is_pinned(&pinned, old_version)
+            })
+            .cloned()
+            .collect();
+        (outdated, locked.running_binary.clone())
+    };
+
+    remove_unused_clients(&outdated, running_binary.as_deref());
+    rescan_installed(state);
 }
 
-#[cfg(target_os = "windows")]
-fn extract_archive(
-    archive_path: &std::path::Path,
-    target_directory: &std::path::Path,
-) -> Result<(), String> {
-    use std::os::windows::process::CommandExt;
+/// Drops installed versions beyond the `keep_versions` newest ones. The
+/// selected version and versions pinned by a credential are always kept and
+/// do not count towards the limit. The removals happen with the lock released.
+fn apply_retention(state: &Arc<Mutex<ClientManagerState>>) {
+    let pinned = pinned_client_versions(&credentials_directory());
+    let (excess, running_binary) = {
+        let locked = state.lock().unwrap();
+        let Some(keep) = locked.keep_versions.filter(|keep| *keep > 0) else {
+            return;
+        };
+        let excess: Vec<String> = locked
+            .installed
+            .iter()
+            .filter(|version| {
+                locked.selected_version.as_deref() != Some(version.as_str())
+                    && !is_pinned(&pinned, version)
+            })
+            .skip(keep)
+            .cloned()
+            .collect();
+        (excess, locked.running_binary.clone())
+    };
 
-    let archive_str = archive_path.to_string_lossy();
+    if excess.is_empty() {
+        return;
+    }
+    remove_unused_clients(&excess, running_binary.as_deref());
+    rescan_installed(state);
+}
 
-    if archive_str.ends_with(".zip") {
-        let script = format!(
-            "Expand-Archive -Path '{}' -DestinationPath '{}' -Force",
-            archive_path.display(),
-            target_directory.display()
-        );
-        let output = std::process::Command::new("powershell")
-            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
-            .stdin(std::process::Stdio::null())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .creation_flags(0x08000000)
-            .output()
-            .map_err(|error| format!("Failed to run powershell: {error}"))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!(
-                "Zip extraction failed (exit {}): {}",
-                output.status.code().unwrap_or(-1),
-                stderr.trim()
-            ));
+/// Applies a changed `keep_versions` in the background.
+pub fn start_retention(state: Arc<Mutex<ClientManagerState>>) {
+    std::thread::spawn(move || apply_retention(&state));
+}
+
+/// Pins match with or without the leading `v`, like [`ClientManagerState::pinned_client`].
+fn is_pinned(pinned: &[String], version: &str) -> bool {
+    pinned
+        .iter()
+        .any(|pin| pin.trim_start_matches('v') == version.trim_start_matches('v'))
+}
+
+fn remove_unused_clients(versions: &[String], running_binary: Option<&Path>) {
+    for version in versions {
+        if is_version_in_use(version, running_binary) {
+            log::info!("[client_manager] keeping {version}, a session is running from it");
+            continue;
+        }
+        if let Err(error) = remove_client(version) {
+            log::warn!("[client_manager] failed to clean up {version}: {error}");
         }
+    }
+}
+
+/// Whether a client session (ours or one started elsewhere, e.g. the headless
+/// CLI) is running a binary from `version`'s directory.
+fn is_version_in_use(version: &str, running_binary: Option<&Path>) -> bool {
+    let directory = clients_directory().join(version);
+    if running_binary.is_some_and(|binary| binary.starts_with(&directory)) {
+        return true;
+    }
+    #[cfg(target_os = "linux")]
+    if process_running_from(&directory) {
+        return true;
+    }
+    false
+}
+
+/// Looks at `/proc/*/cmdline` rather than `exe`, which is unreadable for the
+/// elevated processes TUN mode runs as.
+#[cfg(target_os = "linux")]
+fn process_running_from(directory: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let is_process = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.bytes().all(|byte| byte.is_ascii_digit()));
+        is_process
+            && std::fs::read(entry.path().join("cmdline")).is_ok_and(|cmdline| {
+                cmdline
+                    .split(|byte| *byte == 0)
+                    .filter(|argument| !argument.is_empty())
+                    .any(|argument| {
+                        Path::new(std::ffi::OsStr::from_bytes(argument)).starts_with(directory)
+                    })
+            })
+    })
+}
+
+fn directory_size(directory: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => directory_size(&entry.path()),
+            Ok(file_type) if file_type.is_file() => {
+                entry.metadata().map(|metadata| metadata.len()).unwrap_or(0)
+            }
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Upper bound on the total number of bytes written while unpacking a release.
+const MAX_EXTRACTED_SIZE: u64 = 256 * 1024 * 1024;
+
+fn client_binary_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "trusttunnel_client.exe"
+    } else {
+        "trusttunnel_client"
+    }
+}
+
+/// The client binary plus the shared libraries shipped next to it; everything
+/// else in a release archive (docs, sample configs) is left unpacked.
+fn is_client_file(file_name: &str) -> bool {
+    if file_name == client_binary_name() {
+        return true;
+    }
+    if cfg!(target_os = "windows") {
+        file_name.to_ascii_lowercase().ends_with(".dll")
+    } else if cfg!(target_os = "macos") {
+        file_name.ends_with(".dylib")
     } else {
-        let mut command = std::process::Command::new("tar");
-        command
-            .args(["xzf"])
-            .arg(archive_path)
-            .arg("-C")
-            .arg(target_directory)
-            .stdin(std::process::Stdio::null())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped());
+        file_name.ends_with(".so") || file_name.contains(".so.")
+    }
+}
 
-        command.creation_flags(0x08000000);
+fn extract_archive(archive_path: &Path, target_directory: &Path) -> Result<(), String> {
+    let archive_name = archive_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+
+    if archive_name.ends_with(".zip") {
+        extract_zip(archive_path, target_directory)
+    } else if archive_name.ends_with(".tar.gz") || archive_name.ends_with(".tgz") {
+        extract_tar_gz(archive_path, target_directory)
+    } else {
+        Err(format!("Unsupported archive format: {archive_name}"))
+    }
+}
+
+fn extract_tar_gz(archive_path: &Path, target_directory: &Path) -> Result<(), String> {
+    let file = std::fs::File::open(archive_path)
+        .map_err(|error| format!("Failed to open archive: {error}"))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let mut extraction = ArchiveExtraction::new(target_directory);
+
+    let entries = archive
+        .entries()
+        .map_err(|error| format!("Failed to read tar archive: {error}"))?;
+
+    for entry in entries {
+        let mut entry = entry.map_err(|error| format!("Failed to read tar entry: {error}"))?;
+        let raw_path = entry
+            .path()
+            .map_err(|error| format!("Invalid tar entry path: {error}"))?
+            .into_owned();
+        let entry_path = sanitize_entry_path(&raw_path)?;
+        let entry_type = entry.header().entry_type();
+
+        if entry_type.is_symlink() || entry_type.is_hard_link() {
+            let target = entry
+                .link_name()
+                .map_err(|error| format!("Invalid link target in {}: {error}", raw_path.display()))?
+                .ok_or_else(|| format!("Link {} has no target", raw_path.display()))?
+                .into_owned();
+            // Symlinks resolve against their own directory, hard links against the archive root.
+            let base = if entry_type.is_symlink() {
+                entry_path.parent().unwrap_or(Path::new(""))
+            } else {
+                Path::new("")
+            };
+            check_link_target(&raw_path, base, &target)?;
+            extraction.record_link(&entry_path, base, &target);
+        } else if entry_type.is_file() {
+            let declared_size = entry.size();
+            extraction.extract_file(&entry_path, declared_size, &mut entry)?;
+        }
+    }
+
+    extraction.finish()
+}
+
+fn extract_zip(archive_path: &Path, target_directory: &Path) -> Result<(), String> {
+    let file = std::fs::File::open(archive_path)
+        .map_err(|error| format!("Failed to open archive: {error}"))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|error| format!("Failed to read zip archive: {error}"))?;
+    let mut extraction = ArchiveExtraction::new(target_directory);
+
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|error| format!("Failed to read zip entry: {error}"))?;
+        let raw_path = PathBuf::from(entry.name().replace('\\', "/"));
+        let entry_path = sanitize_entry_path(&raw_path)?;
+
+        if entry.is_dir() {
+            continue;
+        }
+
+        if entry.is_symlink() {
+            let mut target = String::new();
+            entry
+                .by_ref()
+                .take(4096)
+                .read_to_string(&mut target)
+                .map_err(|error| {
+                    format!("Invalid link target in {}: {error}", raw_path.display())
+                })?;
+            let target = PathBuf::from(target.replace('\\', "/"));
+            let base = entry_path.parent().unwrap_or(Path::new(""));
+            check_link_target(&raw_path, base, &target)?;
+            extraction.record_link(&entry_path, base, &target);
+        } else {
+            let declared_size = entry.size();
+            extraction.extract_file(&entry_path, declared_size, &mut entry)?;
+        }
+    }
 
-        let output = command
-            .output()
-            .map_err(|error| format!("Failed to run tar: {error}"))?;
+    extraction.finish()
+}
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!(
-                "tar extraction failed (exit {}): {}",
-                output.status.code().unwrap_or(-1),
-                stderr.trim()
-            ));
+/// Turns an archive entry name into a relative path, refusing anything that
+/// could land outside the version directory.
+fn sanitize_entry_path(raw_path: &Path) -> Result<PathBuf, String> {
+    let mut sanitized = PathBuf::new();
+    for component in raw_path.components() {
+        match component {
+            Component::Normal(part) => sanitized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                return Err(format!(
+                    "Archive entry {} escapes the version directory",
+                    raw_path.display()
+                ));
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(format!(
+                    "Archive entry {} has an absolute path",
+                    raw_path.display()
+                ));
+            }
         }
     }
+    Ok(sanitized)
+}
 
+fn check_link_target(raw_path: &Path, base: &Path, target: &Path) -> Result<(), String> {
+    let mut depth = base.components().count();
+    for component in target.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if depth == 0 {
+                    return Err(format!(
+                        "Link {} points outside the version directory",
+                        raw_path.display()
+                    ));
+                }
+                depth -= 1;
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(format!(
+                    "Link {} points to an absolute path",
+                    raw_path.display()
+                ));
+            }
+        }
+    }
     Ok(())
 }
 
-fn find_binary_recursive(directory: &std::path::Path) -> Option<PathBuf> {
-    let binary_name = if cfg!(target_os = "windows") {
-        "trusttunnel_client.exe"
-    } else {
-        "trusttunnel_client"
-    };
+/// Writes the wanted files of an archive flat into the version directory while
+/// keeping track of the extraction size budget.
+struct ArchiveExtraction<'a> {
+    target_directory: &'a Path,
+    remaining: u64,
+    extracted: HashMap<PathBuf, String>,
+    links: Vec<(String, PathBuf)>,
+}
 
-    let entries = std::fs::read_dir(directory).ok()?;
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_file()
-            && let Some(name) = path.file_name().and_then(|n| n.to_str())
-            && name == binary_name
-        {
-            return Some(path);
-        } else if path.is_dir()
-            && let Some(found) = find_binary_recursive(&path)
-        {
-            return Some(found);
+impl<'a> ArchiveExtraction<'a> {
+    fn new(target_directory: &'a Path) -> Self {
+        Self {
+            target_directory,
+            remaining: MAX_EXTRACTED_SIZE,
+            extracted: HashMap::new(),
+            links: Vec::new(),
         }
     }
-    None
+
+    fn extract_file(
+        &mut self,
+        entry_path: &Path,
+        declared_size: u64,
+        reader: &mut dyn Read,
+    ) -> Result<(), String> {
+        let Some(file_name) = entry_path.file_name().and_then(|name| name.to_str()) else {
+            return Ok(());
+        };
+        if !is_client_file(file_name) {
+            log::debug!("[client_manager] skipping {}", entry_path.display());
+            return Ok(());
+        }
+        if self.extracted.values().any(|name| name == file_name) {
+            return Err(format!("Archive contains more than one {file_name}"));
+        }
+        if declared_size > self.remaining {
+            return Err(self.size_limit_error());
+        }
+
+        let destination = self.target_directory.join(file_name);
+        let mut file = std::fs::File::create(&destination)
+            .map_err(|error| format!("Failed to create {}: {error}", destination.display()))?;
+        let written = std::io::copy(&mut reader.take(self.remaining + 1), &mut file)
+            .map_err(|error| format!("Failed to extract {file_name}: {error}"))?;
+        if written > self.remaining {
+            drop(file);
+            let _ = std::fs::remove_file(&destination);
+            return Err(self.size_limit_error());
+        }
+
+        self.remaining -= written;
+        self.extracted
+            .insert(entry_path.to_path_buf(), file_name.to_string());
+        Ok(())
+    }
+
+    fn record_link(&mut self, entry_path: &Path, base: &Path, target: &Path) {
+        let Some(file_name) = entry_path.file_name().and_then(|name| name.to_str()) else {
+            return;
+        };
+        if is_client_file(file_name) {
+            self.links
+                .push((file_name.to_string(), normalize_link_target(base, target)));
+        }
+    }
+
+    /// Materializes in-archive links to extracted files as plain copies, so
+    /// nothing in the version directory ever points elsewhere.
+    fn finish(mut self) -> Result<(), String> {
+        for (link_name, target) in std::mem::take(&mut self.links) {
+            if self.extracted.values().any(|name| *name == link_name) {
+                continue;
+            }
+            let Some(target_name) = self.extracted.get(&target).cloned() else {
+                log::debug!(
+                    "[client_manager] skipping link {link_name} to unextracted {}",
+                    target.display()
+                );
+                continue;
+            };
+
+            let destination = self.target_directory.join(&link_name);
+            let size = std::fs::metadata(self.target_directory.join(&target_name))
+                .map(|metadata| metadata.len())
+                .unwrap_or(0);
+            if size > self.remaining {
+                return Err(self.size_limit_error());
+            }
+            std::fs::copy(self.target_directory.join(&target_name), &destination)
+                .map_err(|error| format!("Failed to create {link_name}: {error}"))?;
+            self.remaining -= size;
+            self.extracted.insert(PathBuf::from(&link_name), link_name);
+        }
+        Ok(())
+    }
+
+    fn size_limit_error(&self) -> String {
+        format!(
+            "Archive exceeds the {} MiB extraction limit",
+            MAX_EXTRACTED_SIZE / (1024 * 1024)
+        )
+    }
 }
 
-fn cleanup_empty_directories(base_directory: &std::path::Path, keep_path: &std::path::Path) {
-    if let Ok(entries) = std::fs::read_dir(base_directory) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() && path != keep_path.parent().unwrap_or(base_directory) {
-                let _ = std::fs::remove_dir_all(&path);
+fn normalize_link_target(base: &Path, target: &Path) -> PathBuf {
+    let mut resolved: Vec<&std::ffi::OsStr> = base
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part),
+            _ => None,
+        })
+        .collect();
+    for component in target.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::ParentDir => {
+                resolved.pop();
             }
+            _ => {}
         }
     }
+    resolved.iter().collect()
 }
 
-fn version_sort_key(tag: &str) -> Vec<u64> {
-    tag.trim_start_matches('v')
+/// Numeric components of a tag plus whether it is a final release, so
+/// `v1.2.0-beta.1` sorts after `v1.1.9` but before `v1.2.0`.
+pub fn version_sort_key(tag: &str) -> (Vec<u64>, bool) {
+    let tag = tag.trim_start_matches('v');
+    let (core, suffix) = tag.split_once(['-', '+']).unwrap_or((tag, ""));
+    let parts = core
         .split('.')
-        .map(|part| part.parse::<u64>().unwrap_or(0))
-        .collect()
+        .map(|part| {
+            let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse::<u64>().unwrap_or(0)
+        })
+        .collect();
+    (
+        parts,
+        suffix.is_empty() || tag.as_bytes()[core.len()] == b'+',
+    )
 }
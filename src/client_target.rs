@@ -0,0 +1,388 @@
+use std::{
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+    sync::OnceLock,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Libc {
+    Gnu,
+    Musl,
+}
+
+/// The platform the client binary has to run on, detected at runtime rather
+/// than taken from how the UI itself was built.
+#[derive(Debug)]
+pub struct Target {
+    pub operating_system: &'static str,
+    /// Canonical architecture name, e.g. `x86_64`, `armv7hf` or `riscv64`.
+    pub architecture: &'static str,
+    pub libc: Option<Libc>,
+}
+
+impl Target {
+    pub fn triple(&self) -> String {
+        let (architecture, abi) = match self.architecture {
+            "armv7hf" => ("armv7", "eabihf"),
+            "armv7" => ("armv7", "eabi"),
+            "riscv64" => ("riscv64gc", ""),
+            "x86" => ("i686", ""),
+            architecture => (architecture, ""),
+        };
+        match (self.operating_system, self.libc) {
+            ("linux", Some(libc)) => {
+                let libc = if libc == Libc::Musl { "musl" } else { "gnu" };
+                format!("{architecture}-unknown-linux-{libc}{abi}")
+            }
+            ("macos", _) => format!("{architecture}-apple-darwin"),
+            ("windows", _) => format!("{architecture}-pc-windows-msvc"),
+            (operating_system, _) => format!("{architecture}-unknown-{operating_system}"),
+        }
+    }
+
+    fn operating_system_aliases(&self) -> &'static [&'static str] {
+        match self.operating_system {
+            "macos" => &["macos", "darwin", "apple", "osx"],
+            "windows" => &["windows", "win64", "win"],
+            _ => &["linux"],
+        }
+    }
+
+    /// Asset spellings of the architecture, best match first. Generic names
+    /// come last so that an exact build always wins over a compatible one.
+    fn architecture_aliases(&self) -> Vec<&'static str> {
+        let mut aliases = Vec::new();
+        if self.operating_system == "macos" {
+            aliases.push("universal");
+        }
+        aliases.extend_from_slice(match self.architecture {
+            "x86_64" => &["x86_64", "amd64", "x64"][..],
+            "x86" => &["i686", "i586", "i386", "x86"],
+            "aarch64" => &["aarch64", "arm64"],
+            "armv7hf" => &["armv7hf", "armhf", "armv7", "arm"],
+            "armv7" => &["armv7", "armel", "arm"],
+            "riscv64" => &["riscv64gc", "riscv64"],
+            _ => &[],
+        });
+        aliases
+    }
+
+    /// Ranks a release asset for this target, lower is better. `None` means
+    /// the asset is for another platform.
+    pub fn asset_rank(&self, asset_name: &str) -> Option<(usize, usize)> {
+        let name = asset_name.to_ascii_lowercase();
+        let stem = [".tar.gz", ".tgz", ".zip"]
+            .iter()
+            .find_map(|extension| name.strip_suffix(extension))?;
+        let tokens: Vec<&str> = stem.split(['-', '.']).collect();
+        // `x86` must match exactly, as `x86_` starts `x86_64`.
+        let has_token = |alias: &str| {
+            tokens.iter().any(|token| {
+                *token == alias
+                    || (alias != "x86"
+                        && token
+                            .strip_prefix(alias)
+                            .is_some_and(|rest| rest.starts_with('_')))
+            })
+        };
+
+        if !self
+            .operating_system_aliases()
+            .iter()
+            .any(|alias| has_token(alias))
+        {
+            return None;
+        }
+        let architecture_rank = self
+            .architecture_aliases()
+            .iter()
+            .position(|alias| has_token(alias))?;
+
+        let asset_libc = if name.contains("musl") {
+            Some(Libc::Musl)
+        } else if name.contains("gnu") || name.contains("glibc") {
+            Some(Libc::Gnu)
+        } else {
+            None
+        };
+        // Unmarked Linux builds are usually glibc. musl builds are usually
+        // static, so they are the fallback on glibc systems, not the reverse.
+        let libc_rank = match (self.libc, asset_libc) {
+            (Some(Libc::Musl), Some(Libc::Musl)) => 0,
+            (Some(Libc::Musl), None) => 1,
+            (Some(Libc::Musl), Some(Libc::Gnu)) => 2,
+            (_, Some(Libc::Musl)) => 1,
+            _ => 0,
+        };
+
+        Some((architecture_rank, libc_rank))
+    }
+}
+
+pub fn current() -> &'static Target {
+    static TARGET: OnceLock<Target> = OnceLock::new();
+    TARGET.get_or_init(|| {
+        let target = detect();
+        log::info!("[client_target] detected {}", target.triple());
+        target
+    })
+}
+
+fn detect() -> Target {
+    let operating_system = if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "macos"
+    } else {
+        "linux"
+    };
+
+    let libc = (operating_system == "linux").then(detect_libc);
+
+    let architecture = match runtime_architecture() {
+        "x86_64" => "x86_64",
+        "x86" => "x86",
+        "aarch64" => "aarch64",
+        "riscv64" => "riscv64",
+        "arm" if has_soft_float_loader() => "armv7",
+        "arm" => "armv7hf",
+        other => {
+            log::warn!("[client_target] no client builds are known for architecture {other}");
+            "unknown"
+        }
+    };
+
+    Target {
+        operating_system,
+        architecture,
+        libc,
+    }
+}
+
+/// The architecture of the running system in `std::env::consts::ARCH`
+/// spelling. A 32-bit UI build on a 64-bit kernel moves up to the kernel's
+/// architecture, on Linux only when a loader for it is installed, since a
+/// 32-bit userland cannot start dynamically linked 64-bit clients.
+fn runtime_architecture() -> &'static str {
+    let build = std::env::consts::ARCH;
+    let Some(machine) = kernel_machine().as_deref().and_then(normalize_machine) else {
+        return build;
+    };
+    if machine == build {
+        return build;
+    }
+    #[cfg(target_os = "linux")]
+    if !has_loader_for(machine) {
+        log::info!("[client_target] {machine} kernel without a {machine} loader, using {build}");
+        return build;
+    }
+    machine
+}
+
+fn normalize_machine(machine: &str) -> Option<&'static str> {
+    match machine.to_ascii_lowercase().as_str() {
+        "x86_64" | "amd64" => Some("x86_64"),
+        "x86" | "i386" | "i486" | "i586" | "i686" => Some("x86"),
+        "aarch64" | "arm64" | "armv8b" => Some("aarch64"),
+        "riscv64" => Some("riscv64"),
+        machine if machine.starts_with("arm") => Some("arm"),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn kernel_machine() -> Option<String> {
+    // SAFETY: `uname` only fills the zeroed struct, whose fields are
+    // NUL-terminated on success.
+    let mut name: libc::utsname = unsafe { std::mem::zeroed() };
+    if unsafe { libc::uname(&mut name) } != 0 {
+        return None;
+    }
+    let machine = unsafe { std::ffi::CStr::from_ptr(name.machine.as_ptr()) };
+    Some(machine.to_string_lossy().into_owned())
+}
+
+/// A 32-bit process under WOW64 sees the native architecture only in
+/// `PROCESSOR_ARCHITEW6432`.
+#[cfg(target_os = "windows")]
+fn kernel_machine() -> Option<String> {
+    std::env::var("PROCESSOR_ARCHITEW6432")
+        .or_else(|_| std::env::var("PROCESSOR_ARCHITECTURE"))
+        .ok()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn kernel_machine() -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn has_loader_for(architecture: &str) -> bool {
+    let prefixes: &[&str] = match architecture {
+        "x86_64" => &["ld-linux-x86-64.", "ld-musl-x86_64."],
+        "x86" => &["ld-linux.so.2", "ld-musl-i386."],
+        "aarch64" => &["ld-linux-aarch64.", "ld-musl-aarch64."],
+        "riscv64" => &["ld-linux-riscv64-", "ld-musl-riscv64."],
+        "arm" => &["ld-linux-armhf.", "ld-linux.so.3", "ld-musl-arm"],
+        _ => return false,
+    };
+    loader_names()
+        .iter()
+        .any(|name| prefixes.iter().any(|prefix| name.starts_with(prefix)))
+}
+
+fn loader_names() -> Vec<String> {
+    ["/lib", "/lib64"]
+        .iter()
+        .filter_map(|directory| std::fs::read_dir(directory).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.starts_with("ld-"))
+        .collect()
+}
+
+/// Distributions that ship musl next to glibc (Debian's `musl` package, for
+/// one) still run glibc binaries, so a glibc loader takes precedence.
+fn detect_libc() -> Libc {
+    let loaders = loader_names();
+    if loaders.iter().any(|name| name.starts_with("ld-linux")) {
+        Libc::Gnu
+    } else if loaders.iter().any(|name| name.starts_with("ld-musl-")) {
+        Libc::Musl
+    } else if cfg!(target_env = "musl") {
+        Libc::Musl
+    } else {
+        Libc::Gnu
+    }
+}
+
+fn has_soft_float_loader() -> bool {
+    let loaders = loader_names();
+    let hard_float = loaders
+        .iter()
+        .any(|name| name == "ld-linux-armhf.so.3" || name == "ld-musl-armhf.so.1");
+    let soft_float = loaders
+        .iter()
+        .any(|name| name == "ld-linux.so.3" || name == "ld-musl-arm.so.1");
+    soft_float && !hard_float
+}
+
+const ELF_MAGIC: &[u8; 4] = b"\x7fELF";
+const ELF_CLASS_32: u8 = 1;
+const ELF_CLASS_64: u8 = 2;
+const ELF_DATA_LITTLE_ENDIAN: u8 = 1;
+const PT_INTERP: u32 = 3;
+const EF_ARM_ABI_FLOAT_SOFT: u32 = 0x200;
+const EF_ARM_ABI_FLOAT_HARD: u32 = 0x400;
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// Rejects a Linux client binary built for another architecture, float ABI
+/// or a dynamic loader this system does not have. Other platforms pass.
+pub fn check_binary(target: &Target, binary_path: &Path) -> Result<(), String> {
+    if target.operating_system != "linux" {
+        return Ok(());
+    }
+    let (expected_machine, expected_class, machine_name) = match target.architecture {
+        "x86_64" => (62, ELF_CLASS_64, "x86_64"),
+        "x86" => (3, ELF_CLASS_32, "x86"),
+        "aarch64" => (183, ELF_CLASS_64, "aarch64"),
+        "armv7" | "armv7hf" => (40, ELF_CLASS_32, "arm"),
+        "riscv64" => (243, ELF_CLASS_64, "riscv64"),
+        _ => return Ok(()),
+    };
+
+    let mut file = std::fs::File::open(binary_path)
+        .map_err(|error| format!("Failed to open {}: {error}", binary_path.display()))?;
+    let mut header = [0u8; 64];
+    let header_length = file
+        .read(&mut header)
+        .map_err(|error| format!("Failed to read {}: {error}", binary_path.display()))?;
+
+    if header_length < 52 || &header[..4] != ELF_MAGIC {
+        return Err("Client binary is not a Linux executable".into());
+    }
+    let class = header[4];
+    if header[5] != ELF_DATA_LITTLE_ENDIAN || (class == ELF_CLASS_64 && header_length < 64) {
+        return Err("Client binary has an unsupported ELF layout".into());
+    }
+    let machine = read_u16(&header, 18);
+    if machine != expected_machine || class != expected_class {
+        return Err(format!(
+            "Client binary is built for another architecture (ELF machine {machine}), this system needs {machine_name}"
+        ));
+    }
+
+    if machine_name == "arm" {
+        let flags = read_u32(&header, 36);
+        if target.architecture == "armv7hf" && flags & EF_ARM_ABI_FLOAT_SOFT != 0 {
+            return Err("Client binary uses soft-float, this system is armhf".into());
+        }
+        if target.architecture == "armv7" && flags & EF_ARM_ABI_FLOAT_HARD != 0 {
+            return Err("Client binary uses hard-float, this system is armel".into());
+        }
+    }
+
+    let (program_offset, entry_size, entry_count) = if class == ELF_CLASS_64 {
+        (
+            read_u64(&header, 32),
+            read_u16(&header, 54),
+            read_u16(&header, 56),
+        )
+    } else {
+        (
+            read_u32(&header, 28) as u64,
+            read_u16(&header, 42),
+            read_u16(&header, 44),
+        )
+    };
+    let minimum_entry_size = if class == ELF_CLASS_64 { 40 } else { 24 };
+    if (entry_size as usize) < minimum_entry_size {
+        return Ok(());
+    }
+    // One entry at a time, since the header counts come from the file.
+    let mut entry_buffer = [0u8; 40];
+    let entry = &mut entry_buffer[..minimum_entry_size];
+    for index in 0..entry_count as u64 {
+        let entry_offset = program_offset.saturating_add(index * entry_size as u64);
+        file.seek(SeekFrom::Start(entry_offset))
+            .and_then(|_| file.read_exact(entry))
+            .map_err(|error| format!("Failed to read ELF program headers: {error}"))?;
+        if read_u32(entry, 0) != PT_INTERP {
+            continue;
+        }
+        let (offset, size) = if class == ELF_CLASS_64 {
+            (read_u64(entry, 8), read_u64(entry, 32))
+        } else {
+            (read_u32(entry, 4) as u64, read_u32(entry, 16) as u64)
+        };
+        let mut interpreter = vec![0u8; size.min(4096) as usize];
+        file.seek(SeekFrom::Start(offset))
+            .and_then(|_| file.read_exact(&mut interpreter))
+            .map_err(|error| format!("Failed to read ELF interpreter: {error}"))?;
+        let interpreter = String::from_utf8_lossy(&interpreter)
+            .trim_end_matches('\0')
+            .to_string();
+        if !Path::new(&interpreter).exists() {
+            let hint = match target.libc {
+                Some(Libc::Musl) => " (a glibc build on a musl system?)",
+                _ => "",
+            };
+            return Err(format!(
+                "Client binary needs {interpreter}, which this system does not have{hint}"
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -23,6 +23,18 @@ pub fn field(text: &str, input: impl IntoElement) -> Div {
         .child(input)
 }
 
+pub fn field_error(message: &str) -> Div {
+    div()
+        .px(px(PADDING_INPUT_HORIZONTAL))
+        .text_size(px(TEXT_SIZE_EXTRA_SMALL))
+        .text_color(rgb(COLOR_RED))
+        .child(message.to_string())
+}
+
+pub fn validated_field(text: &str, input: impl IntoElement, error: Option<&str>) -> Div {
+    field(text, input).when_some(error, |element, error| element.child(field_error(error)))
+}
+
 pub fn credential_item(
     name: &str,
     active: bool,
@@ -296,11 +308,13 @@ pub fn titlebar_tab(
     text: &str,
     active: bool,
     disabled: bool,
+    badge: bool,
 ) -> Stateful<Div> {
     div()
         .id(id)
         .flex()
         .items_center()
+        .gap(px(GAP_EXTRA_SMALL))
         .px(px(PADDING_COLUMN + PADDING_INPUT_HORIZONTAL))
         .h(px(TITLEBAR_HEIGHT))
         .text_size(px(TEXT_SIZE_SMALL))
@@ -328,9 +342,23 @@ pub fn titlebar_tab(
                 .opacity(0.5)
         })
         .child(text.to_string())
+        .when(badge, |element| {
+            element.child(
+                div()
+                    .size(px(BADGE_SIZE))
+                    .rounded(px(BADGE_SIZE / 2.0))
+                    .bg(rgb(BUTTON_PRIMARY)),
+            )
+        })
 }
 
-pub fn version_item(tag: &str, active: bool, disabled: bool, focus_handle: &FocusHandle) -> Div {
+pub fn version_item(
+    tag: &str,
+    note: Option<&str>,
+    active: bool,
+    disabled: bool,
+    focus_handle: &FocusHandle,
+) -> Div {
     let (background, text_color, border) = match (active, disabled) {
         (true, true) => (BORDER, TEXT_DIM, BORDER),
         (true, false) => (ACTIVE_BACKGROUND, TEXT_PRIMARY, BORDER_STRONG),
@@ -374,8 +402,18 @@ pub fn version_item(tag: &str, active: bool, disabled: bool, focus_handle: &Focu
                         .focus(|style| style.border_color(rgb(BORDER_FOCUS)))
                 })
         })
+        .justify_between()
         .overflow_hidden()
         .child(tag.to_string())
+        .when_some(note, |element, note| {
+            element.child(
+                div()
+                    .flex_shrink_0()
+                    .text_size(px(TEXT_SIZE_EXTRA_SMALL))
+                    .text_color(rgb(COLOR_YELLOW))
+                    .child(note.to_string()),
+            )
+        })
 }
 
 pub fn titlebar_button(id: impl Into<ElementId>, text: &str, danger: bool) -> Stateful<Div> {
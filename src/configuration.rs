@@ -1,8 +1,14 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+use crate::client_capabilities::ClientCapabilities;
+use crate::secret_store;
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct CredentialFile {
     #[serde(default)]
     pub hostname: String,
@@ -12,8 +18,12 @@ pub struct CredentialFile {
     pub has_ipv6: bool,
     #[serde(default)]
     pub username: String,
-    #[serde(default)]
+    /// Resolved in memory; on disk only in legacy files, which are migrated on load.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub password: String,
+    /// Where the password is kept, see [`secret_store`].
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub password_ref: String,
     #[serde(default)]
     pub skip_verification: bool,
     #[serde(default)]
@@ -30,6 +40,17 @@ pub struct CredentialFile {
     pub post_quantum_group_enabled: bool,
     #[serde(default = "default_dns_upstreams")]
     pub dns_upstreams: Vec<String>,
+    #[serde(default)]
+    pub included_routes: Vec<String>,
+    #[serde(default)]
+    pub excluded_routes: Vec<String>,
+    #[serde(default)]
+    pub vpn_mode: String,
+    #[serde(default)]
+    pub exclusions: Vec<String>,
+    /// Client release this endpoint needs; empty uses the Client tab selection.
+    #[serde(default)]
+    pub client_version: String,
 }
 
 fn default_post_quantum_group_enabled() -> bool {
@@ -58,7 +79,7 @@ impl StoredCredential {
             }
         };
 
-        let credential: CredentialFile = match toml::from_str(&content) {
+        let mut credential: CredentialFile = match toml::from_str(&content) {
             Ok(credential) => credential,
             Err(error) => {
                 log::warn!("[credentials] failed to parse {}: {error}", path.display());
@@ -66,6 +87,33 @@ impl StoredCredential {
             }
         };
 
+        if !credential.password_ref.is_empty() {
+            match secret_store::lookup(&credential.password_ref) {
+                Ok(password) => credential.password = password,
+                Err(error) => log::warn!(
+                    "[credentials] password for {} unavailable: {error}",
+                    path.display()
+                ),
+            }
+        } else if !credential.password.is_empty() {
+            match secret_store::store("", &credential.password) {
+                Ok(reference) => {
+                    credential.password_ref = reference;
+                    match write_credential_file(&path, &credential) {
+                        Ok(()) => log::info!(
+                            "[credentials] moved plaintext password of {} to secure storage",
+                            path.display()
+                        ),
+                        Err(error) => log::warn!("[credentials] migration failed: {error}"),
+                    }
+                }
+                Err(error) => log::warn!(
+                    "[credentials] {} keeps a plaintext password until secure storage is available: {error}",
+                    path.display()
+                ),
+            }
+        }
+
         let name = credential_name(&credential, &path);
 
         Some(Self {
@@ -102,10 +150,12 @@ impl StoredCredential {
         let new_path = directory.join(format!("{name}.toml"));
         let old_path = self.path.clone();
 
-        let content = toml::to_string_pretty(&self.credential)
-            .map_err(|error| format!("Failed to serialize credential: {error}"))?;
-        std::fs::write(&new_path, &content)
-            .map_err(|error| format!("Failed to write {}: {error}", new_path.display()))?;
+        // An empty password with a reference means the store was locked at load time.
+        if !self.credential.password.is_empty() {
+            self.credential.password_ref =
+                secret_store::store(&self.credential.password_ref, &self.credential.password)?;
+        }
+        write_credential_file(&new_path, &self.credential)?;
 
         // Remove the old file if the path changed (e.g. draft → named credential)
         // to avoid leaving stale files that scan_credentials would pick up.
@@ -126,6 +176,50 @@ impl StoredCredential {
     }
 }
 
+/// Looks up a saved credential by exact name, then case-insensitively, then by file stem.
+pub fn find_credential_index(credentials: &[StoredCredential], name: &str) -> Option<usize> {
+    let find = |matches: &dyn Fn(&StoredCredential) -> bool| {
+        credentials
+            .iter()
+            .position(|stored| !stored.draft && matches(stored))
+    };
+    find(&|stored| stored.name == name)
+        .or_else(|| find(&|stored| stored.name.eq_ignore_ascii_case(name)))
+        .or_else(|| {
+            find(&|stored| stored.path.file_stem().and_then(|stem| stem.to_str()) == Some(name))
+        })
+}
+
+/// Writes `credential` without its password, readable only by the current user.
+fn write_credential_file(path: &Path, credential: &CredentialFile) -> Result<(), String> {
+    let on_disk = CredentialFile {
+        password: String::new(),
+        ..credential.clone()
+    };
+    let content = toml::to_string_pretty(&on_disk)
+        .map_err(|error| format!("Failed to serialize credential: {error}"))?;
+    write_private_file(path, &content)
+}
+
+pub fn write_private_file(path: &Path, content: &str) -> Result<(), String> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+                .map_err(|error| format!("Failed to restrict {}: {error}", path.display()))?;
+        }
+    }
+    use std::io::Write;
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|error| format!("Failed to write {}: {error}", path.display()))
+}
+
 pub fn credential_name(credential: &CredentialFile, path: &Path) -> String {
     let fallback = path
         .file_stem()
@@ -196,19 +290,59 @@ pub fn scan_credentials(directory: &Path) -> Vec<StoredCredential> {
     result
 }
 
+/// Client versions that stored credentials pin. Reads only that field, so
+/// unlike [`scan_credentials`] it never touches the secret store.
+pub fn pinned_client_versions(directory: &Path) -> Vec<String> {
+    #[derive(Deserialize)]
+    struct PinnedVersion {
+        #[serde(default)]
+        client_version: String,
+    }
+
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().and_then(|extension| extension.to_str()) == Some("toml")
+                && !matches!(
+                    path.file_name().and_then(|name| name.to_str()),
+                    Some("client.toml" | "trusttunnel-ui.toml")
+                )
+        })
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .filter_map(|content| toml::from_str::<PinnedVersion>(&content).ok())
+        .map(|pinned| pinned.client_version.trim().to_string())
+        .filter(|version| !version.is_empty())
+        .collect()
+}
+
 pub fn add_credential_file(source: &Path, directory: &Path) -> Result<PathBuf, String> {
     let content = std::fs::read_to_string(source)
         .map_err(|error| format!("Failed to read {}: {error}", source.display()))?;
-    let credential: CredentialFile = toml::from_str(&content)
+    let mut credential: CredentialFile = toml::from_str(&content)
         .map_err(|error| format!("Failed to parse {}: {error}", source.display()))?;
+    if !credential.password_ref.is_empty() {
+        return Err(format!(
+            "{} refers to a stored password and cannot be imported",
+            source.display()
+        ));
+    }
 
     let base_name = credential_name(&credential, source);
 
     let mut destination = directory.join(format!("{base_name}.toml"));
     let mut counter = 1u32;
     while destination.exists() {
-        let existing = std::fs::read_to_string(&destination).unwrap_or_default();
-        if existing == content {
+        let identical = StoredCredential::from_path(destination.clone()).is_some_and(|existing| {
+            CredentialFile {
+                password_ref: String::new(),
+                ..existing.credential
+            } == credential
+        });
+        if identical {
             log::info!(
                 "[credentials] identical file already exists: {}",
                 destination.display()
@@ -219,8 +353,10 @@ pub fn add_credential_file(source: &Path, directory: &Path) -> Result<PathBuf, S
         counter += 1;
     }
 
-    std::fs::write(&destination, &content)
-        .map_err(|error| format!("Failed to write {}: {error}", destination.display()))?;
+    if !credential.password.is_empty() {
+        credential.password_ref = secret_store::store("", &credential.password)?;
+    }
+    write_credential_file(&destination, &credential)?;
 
     log::info!(
         "[credentials] added {} → {}",
@@ -253,9 +389,148 @@ impl TunnelMode {
             Self::Proxy => "Proxy",
         }
     }
+
+    /// Name used on the command line and in the control socket API.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Tun => "tun",
+            Self::SystemProxy => "system-proxy",
+            Self::Proxy => "proxy",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "tun" => Some(Self::Tun),
+            "system-proxy" | "system_proxy" => Some(Self::SystemProxy),
+            "proxy" => Some(Self::Proxy),
+            _ => None,
+        }
+    }
+}
+
+pub const DEFAULT_PROXY_LISTEN_ADDRESS: &str = "127.0.0.1:1080";
+
+/// Random SOCKS5 credentials generated for a single connection.
+#[derive(Clone)]
+pub struct ProxyCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl ProxyCredentials {
+    pub fn generate() -> Result<Self, String> {
+        let mut bytes = [0u8; 24];
+        getrandom::fill(&mut bytes)
+            .map_err(|error| format!("Failed to generate proxy credentials: {error}"))?;
+        let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+        Ok(Self {
+            username: format!("tt-{}", &hex[..8]),
+            password: hex[8..].to_string(),
+        })
+    }
 }
 
-pub const PROXY_LISTEN_ADDRESS: &str = "127.0.0.1:1080";
+/// Where the client's SOCKS5 listener binds and how it authenticates.
+#[derive(Clone)]
+pub struct ProxyListener {
+    pub address: SocketAddr,
+    pub credentials: Option<ProxyCredentials>,
+}
+
+impl Default for ProxyListener {
+    fn default() -> Self {
+        Self {
+            address: DEFAULT_PROXY_LISTEN_ADDRESS
+                .parse()
+                .expect("default proxy listen address is valid"),
+            credentials: None,
+        }
+    }
+}
+
+impl ProxyListener {
+    /// Checks the port is free and generates per-session credentials when
+    /// `authentication` is set. Errors are `(short message, detail)`.
+    pub fn prepare(address: SocketAddr, authentication: bool) -> Result<Self, (String, String)> {
+        if let Err(error) = std::net::TcpListener::bind(address) {
+            log::warn!("[configuration] SOCKS listen address {address} unavailable: {error}");
+            let message = if error.kind() == std::io::ErrorKind::AddrInUse {
+                format!("Port {} in use", address.port())
+            } else {
+                "SOCKS address unavailable".to_string()
+            };
+            return Err((
+                message,
+                format!(
+                    "Cannot listen on {address}: {error}\n\n\
+                     Choose another SOCKS listen address or stop the application using it."
+                ),
+            ));
+        }
+
+        let credentials = if authentication {
+            Some(
+                ProxyCredentials::generate()
+                    .map_err(|error| ("Proxy credentials failed".to_string(), error))?,
+            )
+        } else {
+            None
+        };
+
+        Ok(Self {
+            address,
+            credentials,
+        })
+    }
+
+    pub fn host(&self) -> String {
+        self.address.ip().to_string()
+    }
+
+    pub fn port(&self) -> u16 {
+        self.address.port()
+    }
+
+    /// `socks5://[user:pass@]host:port`, suitable for `ALL_PROXY`.
+    pub fn url(&self) -> String {
+        match self.credentials {
+            Some(ref credentials) => format!(
+                "socks5://{}:{}@{}",
+                credentials.username, credentials.password, self.address
+            ),
+            None => format!("socks5://{}", self.address),
+        }
+    }
+}
+
+/// Accepts `host:port` or `[v6]:port`; the host must be a literal IP address.
+pub fn parse_proxy_listen_address(text: &str) -> Result<SocketAddr, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("SOCKS listen address is empty".into());
+    }
+    let address: SocketAddr = text.parse().map_err(|_| {
+        format!("'{text}' is not a valid listen address (expected e.g. 127.0.0.1:1080)")
+    })?;
+    if address.port() == 0 {
+        return Err(format!("'{text}' must specify a non-zero port"));
+    }
+    Ok(address)
+}
+
+/// Used when a credential does not define its own `included_routes`.
+pub const DEFAULT_INCLUDED_ROUTES: &[&str] = &["0.0.0.0/0", "2000::/3"];
+
+/// Used when a credential does not define its own `excluded_routes`.
+pub const DEFAULT_EXCLUDED_ROUTES: &[&str] = &[
+    "0.0.0.0/8",
+    "10.0.0.0/8",
+    "169.254.0.0/16",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "224.0.0.0/3",
+];
 
 #[derive(Serialize)]
 pub struct VpnConfiguration {
@@ -330,42 +605,255 @@ pub struct EndpointFields {
     pub post_quantum_group_enabled: bool,
     pub dns_enabled: bool,
     pub dns_upstreams: Vec<String>,
+    pub included_routes: Vec<String>,
+    pub excluded_routes: Vec<String>,
+    pub vpn_mode: String,
+    pub exclusions: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CredentialField {
+    Hostname,
+    Addresses,
+    Username,
+    Password,
+    Certificate,
+    DnsUpstreams,
+    IncludedRoutes,
+    ExcludedRoutes,
+    UpstreamProtocol,
+    FallbackProtocol,
+    VpnMode,
+    Exclusions,
+    ClientVersion,
+}
+
+pub struct FieldError {
+    pub field: CredentialField,
+    pub title: String,
+    pub detail: String,
+}
+
+impl FieldError {
+    fn new(field: CredentialField, title: &str, detail: impl Into<String>) -> Self {
+        Self {
+            field,
+            title: title.into(),
+            detail: detail.into(),
+        }
+    }
 }
 
 impl CredentialFile {
+    /// The first problem, as a status title and detail.
     pub fn validate(&self) -> Option<(String, String)> {
+        self.validate_fields()
+            .into_iter()
+            .next()
+            .map(|error| (error.title, error.detail))
+    }
+
+    /// Checks every field the way the client will read it and reports at
+    /// most one problem per field.
+    pub fn validate_fields(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
         if self.addresses.is_empty() {
-            return Some((
-                "Addresses required".into(),
-                "Enter at least one endpoint address (e.g. 78.141.223.149:443)".into(),
+            errors.push(FieldError::new(
+                CredentialField::Addresses,
+                "Addresses required",
+                "Enter at least one endpoint address (e.g. 78.141.223.149:443)",
+            ));
+        } else if let Some(problem) = self
+            .addresses
+            .iter()
+            .find_map(|address| address.parse::<EndpointAddress>().err())
+        {
+            errors.push(FieldError::new(
+                CredentialField::Addresses,
+                "Invalid address",
+                format!("{problem} (e.g. 78.141.223.149:443 or [2001:db8::1]:443)"),
             ));
         }
+
         if self.hostname.is_empty() {
-            return Some((
-                "Hostname is required".into(),
-                "Enter the endpoint hostname (e.g. vpn.example.com)".into(),
+            errors.push(FieldError::new(
+                CredentialField::Hostname,
+                "Hostname is required",
+                "Enter the endpoint hostname (e.g. vpn.example.com)",
+            ));
+        } else if !is_valid_hostname(&self.hostname) {
+            errors.push(FieldError::new(
+                CredentialField::Hostname,
+                "Invalid hostname",
+                format!(
+                    "'{}' is not a valid hostname (letters, digits and hyphens in dot-separated labels)",
+                    self.hostname
+                ),
             ));
         }
+
         if self.username.is_empty() {
-            return Some(("Username is required".into(), "Enter your username".into()));
+            errors.push(FieldError::new(
+                CredentialField::Username,
+                "Username is required",
+                "Enter your username",
+            ));
+        } else if self.username.chars().any(char::is_control) {
+            errors.push(FieldError::new(
+                CredentialField::Username,
+                "Invalid username",
+                "The username contains control characters",
+            ));
+        }
+
+        if self.password.is_empty() && !self.password_ref.is_empty() {
+            errors.push(FieldError::new(
+                CredentialField::Password,
+                "Password unavailable",
+                "The saved password could not be read — unlock the credential vault or re-enter it",
+            ));
+        } else if self.password.is_empty() {
+            errors.push(FieldError::new(
+                CredentialField::Password,
+                "Password is required",
+                "Enter your password",
+            ));
+        }
+
+        if let Err(detail) = validate_certificate(&self.certificate) {
+            errors.push(FieldError::new(
+                CredentialField::Certificate,
+                "Invalid certificate",
+                detail,
+            ));
+        }
+
+        if let Some(upstream) = self
+            .dns_upstreams
+            .iter()
+            .find(|upstream| !is_valid_dns_upstream(upstream))
+        {
+            errors.push(FieldError::new(
+                CredentialField::DnsUpstreams,
+                "Invalid DNS upstream",
+                format!(
+                    "'{upstream}' is not a DNS server address or URL \
+                     (e.g. 8.8.8.8, tls://1.1.1.1, https://dns.google/dns-query)"
+                ),
+            ));
+        }
+
+        if let Some(route) = self
+            .included_routes
+            .iter()
+            .find(|route| !is_valid_cidr(route))
+        {
+            errors.push(FieldError::new(
+                CredentialField::IncludedRoutes,
+                "Invalid included route",
+                format!("'{route}' is not a valid CIDR (e.g. 10.0.0.0/8 or 2000::/3)"),
+            ));
+        }
+        if let Some(route) = self
+            .excluded_routes
+            .iter()
+            .find(|route| !is_valid_cidr(route))
+        {
+            errors.push(FieldError::new(
+                CredentialField::ExcludedRoutes,
+                "Invalid excluded route",
+                format!("'{route}' is not a valid CIDR (e.g. 192.168.0.0/16 or fd00::/8)"),
+            ));
+        }
+
+        let upstream = if self.upstream_protocol.is_empty() {
+            "http2"
+        } else {
+            self.upstream_protocol.as_str()
+        };
+        if !UPSTREAM_PROTOCOLS.contains(&upstream) {
+            errors.push(FieldError::new(
+                CredentialField::UpstreamProtocol,
+                "Invalid upstream protocol",
+                format!("Unknown upstream protocol '{upstream}', expected 'http2' or 'http3'"),
+            ));
+        }
+        if !self.upstream_fallback_protocol.is_empty() {
+            if !UPSTREAM_PROTOCOLS.contains(&self.upstream_fallback_protocol.as_str()) {
+                errors.push(FieldError::new(
+                    CredentialField::FallbackProtocol,
+                    "Invalid fallback protocol",
+                    format!(
+                        "Unknown fallback protocol '{}', expected 'http2', 'http3' or none",
+                        self.upstream_fallback_protocol
+                    ),
+                ));
+            } else if self.upstream_fallback_protocol == upstream {
+                errors.push(FieldError::new(
+                    CredentialField::FallbackProtocol,
+                    "Invalid fallback protocol",
+                    "The fallback protocol must differ from the upstream protocol",
+                ));
+            }
         }
-        if self.password.is_empty() {
-            return Some(("Password is required".into(), "Enter your password".into()));
+
+        if !matches!(self.vpn_mode.as_str(), "" | "general" | "selective") {
+            errors.push(FieldError::new(
+                CredentialField::VpnMode,
+                "Invalid VPN mode",
+                format!(
+                    "Unknown VPN mode '{}', expected 'general' or 'selective'",
+                    self.vpn_mode
+                ),
+            ));
         }
-        None
+        if let Some(exclusion) = self
+            .exclusions
+            .iter()
+            .find(|exclusion| !is_valid_exclusion(exclusion))
+        {
+            errors.push(FieldError::new(
+                CredentialField::Exclusions,
+                "Invalid exclusion",
+                format!(
+                    "'{exclusion}' is not a domain, wildcard or CIDR \
+                     (e.g. example.com, *.example.com, 10.0.0.0/8)"
+                ),
+            ));
+        } else if self.vpn_mode == "selective" && self.exclusions.is_empty() {
+            errors.push(FieldError::new(
+                CredentialField::Exclusions,
+                "Exclusions required",
+                "Selective mode only tunnels the listed exclusions — add at least one",
+            ));
+        }
+
+        if !self.client_version.is_empty() && !is_valid_version_tag(&self.client_version) {
+            errors.push(FieldError::new(
+                CredentialField::ClientVersion,
+                "Invalid client version",
+                format!(
+                    "'{}' is not a release version (e.g. v1.2.0)",
+                    self.client_version
+                ),
+            ));
+        }
+
+        errors
     }
 
-    /// Addresses that lack an explicit port get `:443` appended.
+    /// Addresses are normalized to `host:port`, with port 443 where none is
+    /// given and IPv6 literals bracketed.
     pub fn to_endpoint_fields(&self, dns_enabled: bool) -> EndpointFields {
         let addresses = self
             .addresses
             .iter()
-            .map(|address| {
-                if address.contains(':') {
-                    address.clone()
-                } else {
-                    format!("{address}:443")
-                }
+            .map(|address| match address.parse::<EndpointAddress>() {
+                Ok(endpoint) => endpoint
+                    .with_default_port(DEFAULT_ENDPOINT_PORT)
+                    .to_string(),
+                Err(_) => address.clone(),
             })
             .collect();
 
@@ -388,12 +876,283 @@ impl CredentialFile {
             post_quantum_group_enabled: self.post_quantum_group_enabled,
             dns_enabled,
             dns_upstreams: self.dns_upstreams.clone(),
+            included_routes: routes_or_default(&self.included_routes, DEFAULT_INCLUDED_ROUTES),
+            excluded_routes: routes_or_default(&self.excluded_routes, DEFAULT_EXCLUDED_ROUTES),
+            vpn_mode: if self.vpn_mode.is_empty() {
+                "general".into()
+            } else {
+                self.vpn_mode.clone()
+            },
+            exclusions: self.exclusions.clone(),
         }
     }
 }
 
+fn routes_or_default(routes: &[String], defaults: &[&str]) -> Vec<String> {
+    if routes.is_empty() {
+        defaults.iter().map(|route| route.to_string()).collect()
+    } else {
+        routes.to_vec()
+    }
+}
+
+/// Accepts a domain (`example.com`), a wildcard (`*.example.com`), an IP
+/// address or a CIDR.
+pub fn is_valid_exclusion(exclusion: &str) -> bool {
+    if exclusion.contains('/') {
+        return is_valid_cidr(exclusion);
+    }
+    if exclusion.parse::<std::net::IpAddr>().is_ok() {
+        return true;
+    }
+    let domain = exclusion.strip_prefix("*.").unwrap_or(exclusion);
+    is_valid_hostname(domain)
+}
+
+/// RFC 1123 hostname: dot-separated labels of letters, digits and inner
+/// hyphens, at most 253 characters overall. An all-numeric last label is
+/// refused so that mistyped IPv4 addresses do not pass as names.
+pub fn is_valid_hostname(hostname: &str) -> bool {
+    let hostname = hostname.strip_suffix('.').unwrap_or(hostname);
+    if hostname.is_empty() || hostname.len() > 253 {
+        return false;
+    }
+    if hostname
+        .rsplit('.')
+        .next()
+        .is_some_and(|label| label.chars().all(|character| character.is_ascii_digit()))
+    {
+        return false;
+    }
+    hostname.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || character == '-')
+    })
+}
+
+const UPSTREAM_PROTOCOLS: &[&str] = &["http2", "http3"];
+
+const DEFAULT_ENDPOINT_PORT: u16 = 443;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EndpointHost {
+    Ip(IpAddr),
+    Name(String),
+}
+
+/// An endpoint address as entered by the user: an IP address or hostname
+/// with an optional port. IPv6 literals may be bare (`2001:db8::1`) or
+/// bracketed, and must be bracketed when a port follows
+/// (`[2001:db8::1]:443`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EndpointAddress {
+    pub host: EndpointHost,
+    pub port: Option<u16>,
+}
+
+impl EndpointAddress {
+    pub fn with_default_port(self, port: u16) -> Self {
+        Self {
+            port: self.port.or(Some(port)),
+            ..self
+        }
+    }
+
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self.host {
+            EndpointHost::Ip(address) => Some(address),
+            EndpointHost::Name(_) => None,
+        }
+    }
+
+    /// The single-address route covering this endpoint, `/32` or `/128`.
+    /// Hostnames have none.
+    pub fn host_route(&self) -> Option<String> {
+        self.ip().map(|address| match address {
+            IpAddr::V4(ipv4) => format!("{ipv4}/32"),
+            IpAddr::V6(ipv6) => format!("{ipv6}/128"),
+        })
+    }
+}
+
+fn parse_port(address: &str, port: &str) -> Result<u16, String> {
+    match port.parse::<u16>() {
+        Ok(port) if port != 0 => Ok(port),
+        _ => Err(format!("'{address}' has an invalid port '{port}'")),
+    }
+}
+
+impl FromStr for EndpointAddress {
+    type Err = String;
+
+    fn from_str(address: &str) -> Result<Self, String> {
+        if let Some(rest) = address.strip_prefix('[') {
+            let Some((host, after)) = rest.split_once(']') else {
+                return Err(format!("'{address}' is missing the closing ']'"));
+            };
+            let ipv6 = host
+                .parse::<Ipv6Addr>()
+                .map_err(|_| format!("'{host}' in '{address}' is not an IPv6 address"))?;
+            let port = match after {
+                "" => None,
+                _ => match after.strip_prefix(':') {
+                    Some(port) => Some(parse_port(address, port)?),
+                    None => return Err(format!("'{address}' has text after the ']'")),
+                },
+            };
+            return Ok(Self {
+                host: EndpointHost::Ip(IpAddr::V6(ipv6)),
+                port,
+            });
+        }
+
+        // A bare IPv6 literal has no port; its last group is never one.
+        if let Ok(ip) = address.parse::<IpAddr>() {
+            return Ok(Self {
+                host: EndpointHost::Ip(ip),
+                port: None,
+            });
+        }
+        if address.matches(':').count() > 1 {
+            return Err(format!(
+                "'{address}' is not a valid IPv6 address; with a port it must be bracketed"
+            ));
+        }
+
+        let (host, port) = match address.split_once(':') {
+            Some((host, port)) => (host, Some(parse_port(address, port)?)),
+            None => (address, None),
+        };
+        let host = if let Ok(ip) = host.parse::<IpAddr>() {
+            EndpointHost::Ip(ip)
+        } else if is_valid_hostname(host) {
+            EndpointHost::Name(host.to_string())
+        } else {
+            return Err(format!("'{address}' is not an IP address or hostname"));
+        };
+        Ok(Self { host, port })
+    }
+}
+
+impl fmt::Display for EndpointAddress {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.host, self.port) {
+            (EndpointHost::Ip(IpAddr::V6(ipv6)), Some(port)) => {
+                write!(formatter, "[{ipv6}]:{port}")
+            }
+            (EndpointHost::Ip(address), None) => write!(formatter, "{address}"),
+            (EndpointHost::Ip(address), Some(port)) => write!(formatter, "{address}:{port}"),
+            (EndpointHost::Name(name), None) => write!(formatter, "{name}"),
+            (EndpointHost::Name(name), Some(port)) => write!(formatter, "{name}:{port}"),
+        }
+    }
+}
+
+/// The client takes a plain `ip[:port]`, a `udp://`, `tcp://`, `tls://`,
+/// `quic://`, `https://` or `h3://` URL, or an `sdns://` stamp.
+pub fn is_valid_dns_upstream(upstream: &str) -> bool {
+    let Some((scheme, rest)) = upstream.split_once("://") else {
+        return upstream.parse::<SocketAddr>().is_ok()
+            || upstream.parse::<std::net::IpAddr>().is_ok();
+    };
+    let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+    match scheme {
+        "sdns" => {
+            !rest.is_empty()
+                && rest
+                    .chars()
+                    .all(|character| character.is_ascii_alphanumeric() || "-_".contains(character))
+        }
+        "https" | "h3" => authority.parse::<EndpointAddress>().is_ok(),
+        "udp" | "tcp" | "tls" | "quic" => {
+            path.is_empty() && authority.parse::<EndpointAddress>().is_ok()
+        }
+        _ => false,
+    }
+}
+
+/// Release tags such as `v1.2.0` or `1.2.0-beta.1`.
+pub fn is_valid_version_tag(version: &str) -> bool {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let core = version.split(['-', '+']).next().unwrap_or(version);
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() >= 2
+        && parts.iter().all(|part| {
+            !part.is_empty() && part.chars().all(|character| character.is_ascii_digit())
+        })
+}
+
+const PEM_BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+const PEM_END: &str = "-----END CERTIFICATE-----";
+
+/// An empty certificate is fine (system trust store); otherwise every PEM
+/// block must be complete and hold base64 DER.
+fn validate_certificate(certificate: &str) -> Result<(), String> {
+    if certificate.trim().is_empty() {
+        return Ok(());
+    }
+    let mut remaining = certificate;
+    let mut count = 0;
+    while let Some(start) = remaining.find(PEM_BEGIN) {
+        count += 1;
+        let after_begin = &remaining[start + PEM_BEGIN.len()..];
+        let Some((body, rest)) = after_begin.split_once(PEM_END) else {
+            return Err(format!("Certificate {count} has no '{PEM_END}' line"));
+        };
+        if body.contains("-----BEGIN") {
+            return Err(format!("Certificate {count} has no '{PEM_END}' line"));
+        }
+        let body: String = body
+            .chars()
+            .filter(|character| !character.is_whitespace())
+            .collect();
+        // DER certificates start with a long-form SEQUENCE, `MI` in base64.
+        if !body.starts_with("MI") || !is_base64(&body) {
+            return Err(format!(
+                "Certificate {count} is not valid base64-encoded DER"
+            ));
+        }
+        remaining = rest;
+    }
+    if count == 0 {
+        return Err(format!(
+            "Paste the PEM certificate, starting with '{PEM_BEGIN}'"
+        ));
+    }
+    Ok(())
+}
+
+fn is_base64(text: &str) -> bool {
+    let data = text.trim_end_matches('=');
+    text.len() % 4 == 0
+        && text.len() - data.len() <= 2
+        && data
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || "+/".contains(character))
+}
+
+/// Accepts `address/prefix` where the prefix fits the address family.
+pub fn is_valid_cidr(route: &str) -> bool {
+    let Some((address, prefix)) = route.split_once('/') else {
+        return false;
+    };
+    let Ok(prefix) = prefix.parse::<u8>() else {
+        return false;
+    };
+    match address.parse::<std::net::IpAddr>() {
+        Ok(std::net::IpAddr::V4(_)) => prefix <= 32,
+        Ok(std::net::IpAddr::V6(_)) => prefix <= 128,
+        Err(_) => false,
+    }
+}
+
 impl VpnConfiguration {
-    pub fn new(endpoint: EndpointFields, mode: TunnelMode) -> Self {
+    pub fn new(endpoint: EndpointFields, mode: TunnelMode, proxy: &ProxyListener) -> Self {
         log::debug!(
             "[configuration] hostname={}, addresses={:?}, skip_verification={}, has_ipv6={}, upstream={}, fallback={}, anti_dpi={}",
             endpoint.hostname,
@@ -410,39 +1169,42 @@ impl VpnConfiguration {
             ListenerConfiguration {
                 tun: Some(TunConfiguration {
                     bound_interface: String::new(),
-                    included_routes: vec!["0.0.0.0/0".into(), "2000::/3".into()],
-                    excluded_routes: vec![
-                        "0.0.0.0/8".into(),
-                        "10.0.0.0/8".into(),
-                        "169.254.0.0/16".into(),
-                        "172.16.0.0/12".into(),
-                        "192.168.0.0/16".into(),
-                        "224.0.0.0/3".into(),
-                    ],
+                    included_routes: endpoint.included_routes,
+                    excluded_routes: endpoint.excluded_routes,
                     mtu_size: 1280,
                     change_system_dns: endpoint.dns_enabled && cfg!(target_os = "windows"),
                 }),
                 proxy: None,
             }
         } else {
-            log::debug!("[configuration] building proxy listener config on {PROXY_LISTEN_ADDRESS}");
+            log::debug!(
+                "[configuration] building proxy listener config on {} (authentication={})",
+                proxy.address,
+                proxy.credentials.is_some(),
+            );
             ListenerConfiguration {
                 tun: None,
                 proxy: Some(ProxyConfiguration {
-                    address: PROXY_LISTEN_ADDRESS.into(),
-                    username: None,
-                    password: None,
+                    address: proxy.address.to_string(),
+                    username: proxy
+                        .credentials
+                        .as_ref()
+                        .map(|credentials| credentials.username.clone()),
+                    password: proxy
+                        .credentials
+                        .as_ref()
+                        .map(|credentials| credentials.password.clone()),
                 }),
             }
         };
 
         Self {
             loglevel: "info".into(),
-            vpn_mode: "general".into(),
+            vpn_mode: endpoint.vpn_mode,
             killswitch_enabled: endpoint.killswitch_enabled,
             killswitch_allow_ports: vec![],
             post_quantum_group_enabled: endpoint.post_quantum_group_enabled,
-            exclusions: vec![],
+            exclusions: endpoint.exclusions,
             dns_upstreams: endpoint.dns_upstreams,
             endpoint: EndpointConfiguration {
                 hostname: endpoint.hostname,
@@ -464,6 +1226,40 @@ impl VpnConfiguration {
             listener,
         }
     }
+
+    /// Serializes the configuration for a particular client, leaving out the
+    /// keys it does not understand. Returns the TOML and a warning for every
+    /// setting that was dropped.
+    pub fn to_toml(
+        &self,
+        capabilities: &ClientCapabilities,
+    ) -> Result<(String, Vec<String>), String> {
+        let mut table = toml::Table::try_from(self)
+            .map_err(|error| format!("Configuration serialization error: {error}"))?;
+        let warnings = capabilities.adapt(&mut table);
+        let toml_string = toml::to_string_pretty(&table)
+            .map_err(|error| format!("Configuration serialization error: {error}"))?;
+        Ok((toml_string, warnings))
+    }
+
+    /// Keeps traffic to the endpoint itself out of the TUN routes.
+    pub fn exclude_endpoint_addresses(&mut self) {
+        let Some(ref mut tun) = self.listener.tun else {
+            return;
+        };
+        for endpoint_address in &self.endpoint.addresses {
+            let Some(route) = endpoint_address
+                .parse::<EndpointAddress>()
+                .ok()
+                .and_then(|address| address.host_route())
+            else {
+                continue;
+            };
+            if !tun.excluded_routes.contains(&route) {
+                tun.excluded_routes.push(route);
+            }
+        }
+    }
 }
 
 pub fn redact_password_in_toml(toml: &str) -> String {
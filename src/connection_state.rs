@@ -1,8 +1,12 @@
+use std::time::Duration;
+
 #[derive(Clone)]
 pub enum ConnectionState {
     Disconnected,
     Connecting,
     Connected,
+    /// Waiting to respawn the client after an unexpected exit; holds the attempt number.
+    Reconnecting(u32),
     Disconnecting,
     Error(String),
 }
@@ -12,14 +16,48 @@ impl ConnectionState {
         matches!(self, Self::Connected)
     }
 
+    pub fn is_reconnecting(&self) -> bool {
+        matches!(self, Self::Reconnecting(_))
+    }
+
     pub fn is_busy(&self) -> bool {
         matches!(self, Self::Connecting | Self::Disconnecting)
     }
 
+    /// Stable identifier used by the control socket.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Disconnected => "disconnected",
+            Self::Connecting => "connecting",
+            Self::Connected => "connected",
+            Self::Reconnecting(_) => "reconnecting",
+            Self::Disconnecting => "disconnecting",
+            Self::Error(_) => "error",
+        }
+    }
+
     pub fn is_active(&self) -> bool {
         matches!(
             self,
-            Self::Connecting | Self::Connected | Self::Disconnecting
+            Self::Connecting | Self::Connected | Self::Reconnecting(_) | Self::Disconnecting
         )
     }
 }
+
+#[derive(Clone, Copy)]
+pub struct ReconnectPolicy {
+    pub enabled: bool,
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl ReconnectPolicy {
+    /// Doubles the initial delay for every attempt after the first, capped at `max_delay`.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        self.initial_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay)
+    }
+}
@@ -0,0 +1,288 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    os::{
+        fd::AsRawFd,
+        unix::{
+            fs::{DirBuilderExt, PermissionsExt},
+            net::{UnixListener, UnixStream},
+        },
+    },
+    path::PathBuf,
+    sync::{Arc, Mutex, mpsc},
+    time::Duration,
+};
+
+use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+const DEFAULT_TAIL_LINES: usize = 100;
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+const SOCKET_NAME: &str = "control.sock";
+
+/// One JSON object per line, e.g. `{"id": 1, "command": "connect", "name": "work"}`.
+#[derive(Deserialize)]
+struct RequestMessage {
+    #[serde(default)]
+    id: Option<Value>,
+    #[serde(flatten)]
+    command: ControlCommand,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlCommand {
+    GetState,
+    ListCredentials,
+    Connect {
+        name: String,
+        #[serde(default)]
+        mode: Option<String>,
+    },
+    Disconnect,
+    TailLog {
+        #[serde(default = "default_tail_lines")]
+        lines: usize,
+    },
+    /// Imports a credential file and raises the window; used by a second launch.
+    Import {
+        path: PathBuf,
+    },
+    Focus,
+    /// Turns the connection into a stream of `state` and `log` events.
+    Subscribe,
+}
+
+fn default_tail_lines() -> usize {
+    DEFAULT_TAIL_LINES
+}
+
+/// A command forwarded to the UI thread; answer it with [`ControlRequest::respond`].
+pub struct ControlRequest {
+    pub command: ControlCommand,
+    reply: mpsc::Sender<Result<Value, String>>,
+}
+
+impl ControlRequest {
+    pub fn respond(self, result: Result<Value, String>) {
+        let _ = self.reply.send(result);
+    }
+}
+
+/// Fan-out of events to every subscribed connection.
+#[derive(Clone, Default)]
+pub struct ControlEvents {
+    subscribers: Arc<Mutex<Vec<mpsc::Sender<String>>>>,
+}
+
+impl ControlEvents {
+    pub fn emit(&self, event: Value) {
+        let Ok(mut subscribers) = self.subscribers.lock() else {
+            return;
+        };
+        if subscribers.is_empty() {
+            return;
+        }
+        let line = event.to_string();
+        subscribers.retain(|subscriber| subscriber.send(line.clone()).is_ok());
+    }
+
+    fn subscribe(&self) -> mpsc::Receiver<String> {
+        let (sender, receiver) = mpsc::channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(sender);
+        }
+        receiver
+    }
+}
+
+/// Per-user directory holding the control socket and the instance lock,
+/// created with mode 0700.
+pub fn runtime_directory() -> Result<PathBuf, String> {
+    let directory = match dirs::runtime_dir() {
+        Some(runtime) => runtime.join("trusttunnel"),
+        None => std::env::temp_dir().join(format!("trusttunnel-{}", unsafe { libc::getuid() })),
+    };
+
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(&directory)
+        .map_err(|error| format!("failed to create {}: {error}", directory.display()))?;
+    std::fs::set_permissions(&directory, std::fs::Permissions::from_mode(0o700))
+        .map_err(|error| format!("failed to restrict {}: {error}", directory.display()))?;
+    Ok(directory)
+}
+
+/// Binds the socket and serves it on background threads. Returns `None` when
+/// the socket cannot be created or another instance already owns it.
+pub fn start(events: ControlEvents) -> Option<UnboundedReceiver<ControlRequest>> {
+    let path = match runtime_directory() {
+        Ok(directory) => directory.join(SOCKET_NAME),
+        Err(error) => {
+            log::warn!("[control] {error}");
+            return None;
+        }
+    };
+
+    if path.exists() {
+        if UnixStream::connect(&path).is_ok() {
+            log::warn!(
+                "[control] {} is served by another instance, not starting",
+                path.display()
+            );
+            return None;
+        }
+        log::info!("[control] removing stale socket {}", path.display());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    let listener = match UnixListener::bind(&path) {
+        Ok(listener) => listener,
+        Err(error) => {
+            log::warn!("[control] failed to bind {}: {error}", path.display());
+            return None;
+        }
+    };
+    if let Err(error) = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)) {
+        log::warn!("[control] failed to restrict {}: {error}", path.display());
+        let _ = std::fs::remove_file(&path);
+        return None;
+    }
+    log::info!("[control] listening on {}", path.display());
+
+    let (sender, receiver) = unbounded();
+    std::thread::spawn(move || {
+        let own_uid = unsafe { libc::getuid() };
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(error) => {
+                    log::warn!("[control] accept failed: {error}");
+                    continue;
+                }
+            };
+            match peer_uid(&stream) {
+                Some(uid) if uid == own_uid => {}
+                other => {
+                    log::warn!("[control] rejecting connection from uid {other:?}");
+                    continue;
+                }
+            }
+            let sender = sender.clone();
+            let events = events.clone();
+            std::thread::spawn(move || serve_connection(stream, sender, events));
+        }
+    });
+
+    Some(receiver)
+}
+
+fn peer_uid(stream: &UnixStream) -> Option<u32> {
+    let mut credentials = libc::ucred {
+        pid: 0,
+        uid: 0,
+        gid: 0,
+    };
+    let mut length = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            &mut credentials as *mut libc::ucred as *mut libc::c_void,
+            &mut length,
+        )
+    };
+    (result == 0).then_some(credentials.uid)
+}
+
+fn serve_connection(
+    stream: UnixStream,
+    sender: UnboundedSender<ControlRequest>,
+    events: ControlEvents,
+) {
+    let Ok(reader_stream) = stream.try_clone() else {
+        return;
+    };
+    let mut writer = stream;
+
+    for line in BufReader::new(reader_stream).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let message: RequestMessage = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(error) => {
+                let response = json!({ "ok": false, "error": format!("invalid request: {error}") });
+                if writeln!(writer, "{response}").is_err() {
+                    return;
+                }
+                continue;
+            }
+        };
+        let id = message.id.unwrap_or(Value::Null);
+
+        if let ControlCommand::Subscribe = message.command {
+            let receiver = events.subscribe();
+            if writeln!(writer, "{}", json!({ "id": id, "ok": true })).is_err() {
+                return;
+            }
+            for event in receiver {
+                if writeln!(writer, "{event}").is_err() {
+                    return;
+                }
+            }
+            return;
+        }
+
+        let (reply_sender, reply_receiver) = mpsc::channel();
+        let request = ControlRequest {
+            command: message.command,
+            reply: reply_sender,
+        };
+        let response = if sender.unbounded_send(request).is_err() {
+            json!({ "id": id, "ok": false, "error": "application is shutting down" })
+        } else {
+            match reply_receiver.recv_timeout(REPLY_TIMEOUT) {
+                Ok(Ok(result)) => json!({ "id": id, "ok": true, "result": result }),
+                Ok(Err(error)) => json!({ "id": id, "ok": false, "error": error }),
+                Err(_) => json!({ "id": id, "ok": false, "error": "no reply from application" }),
+            }
+        };
+        if writeln!(writer, "{response}").is_err() {
+            return;
+        }
+    }
+}
+
+/// Sends one request to the running instance and returns its `result`.
+pub fn send(request: Value) -> Result<Value, String> {
+    let path = runtime_directory()?.join(SOCKET_NAME);
+    let mut stream = UnixStream::connect(&path)
+        .map_err(|error| format!("failed to connect to {}: {error}", path.display()))?;
+    stream
+        .set_read_timeout(Some(REPLY_TIMEOUT))
+        .map_err(|error| format!("failed to set socket timeout: {error}"))?;
+    writeln!(stream, "{request}").map_err(|error| format!("failed to send request: {error}"))?;
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(|error| format!("failed to read reply: {error}"))?;
+    let mut response: Value = serde_json::from_str(&line)
+        .map_err(|error| format!("invalid reply '{}': {error}", line.trim()))?;
+
+    if response["ok"].as_bool() == Some(true) {
+        Ok(response["result"].take())
+    } else {
+        Err(response["error"]
+            .as_str()
+            .unwrap_or("request failed")
+            .to_string())
+    }
+}
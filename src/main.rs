@@ -5,13 +5,19 @@
 
 mod app;
 mod app_state;
+mod cli;
+mod client_capabilities;
 mod client_manager;
+mod client_target;
 mod components;
 mod configuration;
 mod connection_state;
+#[cfg(target_os = "linux")]
+mod control_socket;
 mod log_panel;
 mod process_log;
-#[cfg(target_os = "windows")]
+mod release_notes;
+mod secret_store;
 mod single_instance;
 mod system;
 mod text_area;
@@ -31,8 +37,9 @@ use crate::{
         SwitchTabPrevious, TrustTunnelApp,
     },
     app_state::{AppState, apply_saved_order},
-    client_manager::{ClientManagerState, scan_installed_clients},
+    client_manager::{ClientManagerState, DEFAULT_RELEASES_URL, load_release_cache},
     configuration::{
+        DEFAULT_EXCLUDED_ROUTES, DEFAULT_INCLUDED_ROUTES, DEFAULT_PROXY_LISTEN_ADDRESS,
         StoredCredential, add_credential_file, credentials_directory, scan_credentials,
     },
     log_panel::LogPanel,
@@ -45,10 +52,21 @@ use crate::{
 };
 
 fn main() {
-    env_logger::Builder::from_env(
-        env_logger::Env::default().default_filter_or("trusttunnel_ui=info"),
-    )
-    .init();
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    let cli_command = cli::parse(&arguments);
+
+    #[cfg(target_os = "windows")]
+    if cli_command.is_some() {
+        system::attach_parent_console();
+    }
+
+    let default_filter = if cli_command.is_some() {
+        "trusttunnel_ui=warn"
+    } else {
+        "trusttunnel_ui=info"
+    };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter))
+        .init();
 
     log::info!(
         "trusttunnel-ui {} starting (RUST_LOG={})",
@@ -70,12 +88,21 @@ fn main() {
         }));
     }
 
-    #[cfg(target_os = "windows")]
+    match cli_command {
+        Some(Ok(command)) => std::process::exit(cli::run(command, system_services)),
+        Some(Err(error)) => std::process::exit(cli::usage_error(&error)),
+        None => {}
+    }
+
     let _single_instance_guard = match single_instance::acquire() {
         Some(guard) => guard,
         None => {
             log::warn!("[application_startup] another instance is already running — exiting");
-            #[cfg(not(debug_assertions))]
+            #[cfg(target_os = "linux")]
+            if let Err(error) = single_instance::forward_to_running_instance(arguments.first()) {
+                log::error!("[application_startup] failed to reach the running instance: {error}");
+            }
+            #[cfg(all(target_os = "windows", not(debug_assertions)))]
             single_instance::show_already_running_message();
             return;
         }
@@ -100,7 +127,6 @@ fn main() {
     system_services.startup_cleanup();
 
     let saved_state_early = AppState::load();
-    let installed_clients = scan_installed_clients();
     let saved_client_version = saved_state_early
         .selected_client_version()
         .map(|v| v.to_string());
@@ -111,6 +137,17 @@ fn main() {
     apply_saved_order(&mut stored_credentials, &saved_state_early.credential_order);
     let saved_tunnel_mode = saved_state_early.tunnel_mode();
     let saved_dns_enabled = saved_state_early.dns_enabled();
+    let saved_proxy_listen_address = saved_state_early
+        .proxy_listen_address
+        .clone()
+        .filter(|address| !address.trim().is_empty());
+    let saved_proxy_authentication = saved_state_early.proxy_authentication();
+    let saved_reconnect_policy = saved_state_early.reconnect_policy();
+    let saved_release_source = saved_state_early.release_source();
+    let saved_release_url = saved_release_source.releases_url.clone();
+    let saved_github_token = saved_release_source.github_token.clone();
+    let saved_prune_after_upgrade = saved_state_early.prune_after_upgrade();
+    let saved_keep_client_versions = saved_state_early.keep_client_versions();
     let saved_selected_credential = saved_state_early.find_selected_index(&stored_credentials);
     let system_services = system_services.clone();
 
@@ -122,8 +159,12 @@ fn main() {
         .run(move |context| {
             let client_manager_state = {
                 let mut client_manager_state_value = ClientManagerState::new(context.http_client());
-                client_manager_state_value.installed = installed_clients;
+                client_manager_state_value.refresh_installed();
                 client_manager_state_value.selected_version = saved_client_version;
+                client_manager_state_value.release_source = saved_release_source;
+                client_manager_state_value.prune_after_upgrade = saved_prune_after_upgrade;
+                client_manager_state_value.keep_versions = saved_keep_client_versions;
+                load_release_cache(&mut client_manager_state_value);
                 Arc::new(Mutex::new(client_manager_state_value))
             };
 
@@ -183,7 +224,7 @@ fn main() {
                     app_id: Some("TrustTunnel".into()),
                     ..Default::default()
                 },
-                |_, context| {
+                |window, context| {
                     let log_panel = context.new(LogPanel::new);
 
                     let selected_credential = initial_credential_snapshot
@@ -207,6 +248,14 @@ fn main() {
                         active_credential.map(|stored| stored.credential.password.as_str());
                     let dns_upstreams_initial =
                         active_credential.map(|stored| stored.credential.dns_upstreams.join(", "));
+                    let included_routes_initial = active_credential
+                        .map(|stored| stored.credential.included_routes.join(", "));
+                    let excluded_routes_initial = active_credential
+                        .map(|stored| stored.credential.excluded_routes.join(", "));
+                    let exclusions_initial =
+                        active_credential.map(|stored| stored.credential.exclusions.join(", "));
+                    let client_version_initial =
+                        active_credential.map(|stored| stored.credential.client_version.as_str());
 
                     let hostname_input =
                         TextInput::new(context, "example.com", false, hostname_initial);
@@ -225,6 +274,52 @@ fn main() {
                         false,
                         dns_upstreams_initial.as_deref(),
                     );
+                    let included_routes_input = TextInput::new(
+                        context,
+                        &DEFAULT_INCLUDED_ROUTES.join(", "),
+                        false,
+                        included_routes_initial.as_deref(),
+                    );
+                    let excluded_routes_input = TextInput::new(
+                        context,
+                        &DEFAULT_EXCLUDED_ROUTES.join(", "),
+                        false,
+                        excluded_routes_initial.as_deref(),
+                    );
+                    let exclusions_input = TextInput::new(
+                        context,
+                        "example.com, *.example.org, 10.0.0.0/8",
+                        false,
+                        exclusions_initial.as_deref(),
+                    );
+                    let client_version_input = TextInput::new(
+                        context,
+                        "Selected in the Client tab",
+                        false,
+                        client_version_initial,
+                    );
+                    let proxy_listen_input = TextInput::new(
+                        context,
+                        DEFAULT_PROXY_LISTEN_ADDRESS,
+                        false,
+                        saved_proxy_listen_address.as_deref(),
+                    );
+                    let vault_passphrase_input =
+                        TextInput::new(context, "Protects saved passwords", true, None);
+                    let vault_confirmation_input =
+                        TextInput::new(context, "Same passphrase again", true, None);
+                    let release_url_input = TextInput::new(
+                        context,
+                        DEFAULT_RELEASES_URL,
+                        false,
+                        saved_release_url.as_deref(),
+                    );
+                    let github_token_input = TextInput::new(
+                        context,
+                        "Optional, raises the GitHub API rate limit",
+                        true,
+                        saved_github_token.as_deref(),
+                    );
 
                     let certificate_initial = active_credential
                         .map(|stored| stored.credential.certificate.trim())
@@ -259,6 +354,10 @@ fn main() {
                     let upstream_fallback_protocol = active_credential
                         .map(|stored| stored.credential.upstream_fallback_protocol.clone())
                         .unwrap_or_default();
+                    let vpn_mode = active_credential
+                        .map(|stored| stored.credential.vpn_mode.clone())
+                        .filter(|mode| !mode.is_empty())
+                        .unwrap_or_else(|| "general".into());
 
                     log::info!(
                         "[application_startup] binary={}, found={}",
@@ -275,14 +374,26 @@ fn main() {
                                 password_input,
                                 certificate_input,
                                 dns_upstreams_input,
+                                included_routes_input,
+                                excluded_routes_input,
+                                exclusions_input,
+                                client_version_input,
+                                proxy_listen_input,
+                                vault_passphrase_input,
+                                vault_confirmation_input,
+                                release_url_input,
+                                github_token_input,
                                 has_ipv6,
                                 skip_verification,
                                 upstream_protocol,
                                 upstream_fallback_protocol,
+                                vpn_mode,
                                 anti_dpi,
                                 killswitch_enabled,
                                 post_quantum_group_enabled,
                                 dns_enabled: saved_dns_enabled,
+                                proxy_authentication: saved_proxy_authentication,
+                                reconnect_policy: saved_reconnect_policy,
                                 configuration_path: configuration_path.clone(),
                                 system_services: system_services.clone(),
                                 log_panel,
@@ -293,6 +404,7 @@ fn main() {
                                 tunnel_mode: saved_tunnel_mode,
                                 client_manager_state: client_manager_state_shared,
                             },
+                            window,
                             context,
                         )
                     })
@@ -1,5 +1,9 @@
+use std::sync::Arc;
+
 const MAX_LOG_LINES: usize = 500;
 
+pub type LineListener = Arc<dyn Fn(&str) + Send + Sync>;
+
 pub enum LogLineEvent {
     Connected,
     ConnectError,
@@ -53,6 +57,7 @@ pub struct ProcessLog {
     pub connected: bool,
     pub error: Option<String>,
     pub post_connect_error: Option<String>,
+    line_listener: Option<LineListener>,
 }
 
 impl ProcessLog {
@@ -62,9 +67,15 @@ impl ProcessLog {
             connected: false,
             error: None,
             post_connect_error: None,
+            line_listener: None,
         }
     }
 
+    /// Called for every pushed line; survives `reset`.
+    pub fn set_line_listener(&mut self, listener: LineListener) {
+        self.line_listener = Some(listener);
+    }
+
     pub fn reset(&mut self) {
         self.lines.clear();
         self.connected = false;
@@ -93,6 +104,10 @@ impl ProcessLog {
             LogLineEvent::Normal => {}
         }
 
+        if let Some(ref listener) = self.line_listener {
+            listener(&line);
+        }
+
         self.lines.push(line);
         if self.lines.len() > MAX_LOG_LINES {
             self.lines.remove(0);
@@ -0,0 +1,207 @@
+use gpui::{Div, FontWeight, div, prelude::*, px, rgb};
+
+use crate::theme::*;
+
+/// The part of GitHub-flavoured markdown that release notes actually use.
+/// Anything else is shown as plain paragraph text.
+enum Block {
+    Heading(String),
+    Item {
+        marker: String,
+        depth: usize,
+        text: String,
+    },
+    Code(String),
+    Paragraph(String),
+}
+
+fn parse(markdown: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut code: Option<Vec<String>> = None;
+    let mut in_comment = false;
+
+    let flush = |paragraph: &mut Vec<String>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(inline(&paragraph.join(" "))));
+            paragraph.clear();
+        }
+    };
+
+    for raw_line in markdown.lines() {
+        let line = raw_line.trim_end();
+        let trimmed = line.trim_start();
+
+        if let Some(lines) = code.as_mut() {
+            if trimmed.starts_with("```") {
+                blocks.push(Block::Code(lines.join("\n")));
+                code = None;
+            } else {
+                lines.push(line.to_string());
+            }
+            continue;
+        }
+        if in_comment {
+            in_comment = !trimmed.contains("-->");
+            continue;
+        }
+        if trimmed.starts_with("<!--") {
+            in_comment = !trimmed.contains("-->");
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            flush(&mut paragraph, &mut blocks);
+            code = Some(Vec::new());
+            continue;
+        }
+        if trimmed.is_empty() || is_rule(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            continue;
+        }
+
+        let hashes = trimmed
+            .chars()
+            .take_while(|&character| character == '#')
+            .count();
+        if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading(inline(trimmed[hashes..].trim())));
+            continue;
+        }
+
+        if let Some((marker, text)) = list_item(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            let indent = line.len() - trimmed.len();
+            blocks.push(Block::Item {
+                marker,
+                depth: indent / 2,
+                text: inline(text),
+            });
+            continue;
+        }
+
+        paragraph.push(trimmed.to_string());
+    }
+
+    if let Some(lines) = code {
+        blocks.push(Block::Code(lines.join("\n")));
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|character| *character != ' ').collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|rule| compact.chars().all(|character| character == *rule))
+}
+
+fn list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(bullet) {
+            let text = text
+                .strip_prefix("[ ] ")
+                .or_else(|| text.strip_prefix("[x] "))
+                .unwrap_or(text);
+            return Some(("•".to_string(), text));
+        }
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        let rest = &line[digits..];
+        if let Some(text) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return Some((format!("{}.", &line[..digits]), text));
+        }
+    }
+    None
+}
+
+/// Drops inline markup: emphasis markers, code ticks and link targets
+/// (`[text](url)` keeps only the text).
+fn inline(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(character) = rest.chars().next() {
+        if rest.starts_with("**") || rest.starts_with("__") || rest.starts_with("~~") {
+            rest = &rest[2..];
+            continue;
+        }
+        if character == '`' {
+            rest = &rest[1..];
+            continue;
+        }
+        let link_start = if rest.starts_with("![") {
+            Some(2)
+        } else if character == '[' {
+            Some(1)
+        } else {
+            None
+        };
+        if let Some(start) = link_start
+            && let Some(close) = rest.find("](")
+            && !rest[start..close].contains(']')
+            && let Some(end) = rest[close..].find(')')
+        {
+            output.push_str(&inline(&rest[start..close]));
+            rest = &rest[close + end + 1..];
+            continue;
+        }
+        output.push(character);
+        rest = &rest[character.len_utf8()..];
+    }
+    output
+}
+
+pub fn release_notes(markdown: &str) -> Div {
+    let blocks = parse(markdown);
+    let mut container = div()
+        .flex()
+        .flex_col()
+        .gap(px(GAP_EXTRA_SMALL))
+        .text_size(px(TEXT_SIZE_SMALL))
+        .text_color(rgb(TEXT_PRIMARY));
+
+    if blocks.is_empty() {
+        return container
+            .text_color(rgb(TEXT_DIM))
+            .child("No release notes published.");
+    }
+
+    for block in blocks {
+        container = container.child(match block {
+            Block::Heading(text) => div()
+                .pt(px(GAP_EXTRA_SMALL))
+                .font_weight(FontWeight::SEMIBOLD)
+                .child(text),
+            Block::Item {
+                marker,
+                depth,
+                text,
+            } => div()
+                .flex()
+                .flex_row()
+                .gap(px(GAP_EXTRA_SMALL))
+                .pl(px(GAP_SMALL * depth as f32))
+                .child(
+                    div()
+                        .flex_shrink_0()
+                        .text_color(rgb(TEXT_DIM))
+                        .child(marker),
+                )
+                .child(div().flex_1().child(text)),
+            Block::Code(text) => div()
+                .px(px(PADDING_INPUT_HORIZONTAL))
+                .py(px(PADDING_INPUT_VERTICAL))
+                .rounded(px(RADIUS))
+                .border_1()
+                .border_color(rgb(BORDER))
+                .text_size(px(TEXT_SIZE_EXTRA_SMALL))
+                .text_color(rgb(LOG_TEXT))
+                .child(text),
+            Block::Paragraph(text) => div().child(text),
+        });
+    }
+    container
+}
@@ -0,0 +1,394 @@
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+};
+
+use argon2::Argon2;
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, KeyInit, Nonce,
+    aead::{Aead, Payload},
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "linux")]
+use crate::system::{run_silent, run_silent_with_output};
+
+#[cfg(target_os = "linux")]
+const SECRET_SERVICE_PREFIX: &str = "secret-service:";
+const VAULT_PREFIX: &str = "vault:";
+/// Lets headless sessions unlock the vault without a prompt.
+pub const VAULT_PASSPHRASE_VARIABLE: &str = "TRUSTTUNNEL_VAULT_PASSPHRASE";
+
+const VAULT_FORMAT_VERSION: u32 = 1;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+/// Saves `password` and returns the reference to keep in the credential file.
+/// `previous` is the credential's current reference, if any; its id is reused.
+pub fn store(previous: &str, password: &str) -> Result<String, String> {
+    let id = match reference_id(previous) {
+        Some(id) => id.to_string(),
+        None => generate_id()?,
+    };
+
+    #[cfg(target_os = "linux")]
+    if secret_service_available() {
+        match secret_service_store(&id, password) {
+            Ok(()) => {
+                if previous.starts_with(VAULT_PREFIX) {
+                    remove(previous);
+                }
+                return Ok(format!("{SECRET_SERVICE_PREFIX}{id}"));
+            }
+            Err(error) => log::warn!("[secrets] {error}, falling back to the vault"),
+        }
+    }
+
+    vault_update(|secrets| {
+        secrets.insert(id.clone(), password.to_string());
+    })?;
+    #[cfg(target_os = "linux")]
+    if previous.starts_with(SECRET_SERVICE_PREFIX) {
+        remove(previous);
+    }
+    Ok(format!("{VAULT_PREFIX}{id}"))
+}
+
+pub fn lookup(reference: &str) -> Result<String, String> {
+    #[cfg(target_os = "linux")]
+    if let Some(id) = reference.strip_prefix(SECRET_SERVICE_PREFIX) {
+        return secret_service_lookup(id);
+    }
+    if let Some(id) = reference.strip_prefix(VAULT_PREFIX) {
+        return vault_read()?
+            .remove(id)
+            .ok_or_else(|| format!("password {id} is missing from the vault"));
+    }
+    Err(format!("unsupported password reference '{reference}'"))
+}
+
+pub fn remove(reference: &str) {
+    #[cfg(target_os = "linux")]
+    if let Some(id) = reference.strip_prefix(SECRET_SERVICE_PREFIX) {
+        if !run_silent(
+            "secret-tool",
+            &["clear", "service", "trusttunnel-ui", "credential", id],
+        ) {
+            log::warn!("[secrets] failed to clear secret {id}");
+        }
+        return;
+    }
+    if let Some(id) = reference.strip_prefix(VAULT_PREFIX)
+        && let Err(error) = vault_update(|secrets| {
+            secrets.remove(id);
+        })
+    {
+        log::warn!("[secrets] failed to remove {id} from the vault: {error}");
+    }
+}
+
+pub fn is_vault_reference(reference: &str) -> bool {
+    reference.starts_with(VAULT_PREFIX)
+}
+
+fn reference_id(reference: &str) -> Option<&str> {
+    #[cfg(target_os = "linux")]
+    if let Some(id) = reference.strip_prefix(SECRET_SERVICE_PREFIX) {
+        return Some(id);
+    }
+    reference.strip_prefix(VAULT_PREFIX)
+}
+
+fn generate_id() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes)
+        .map_err(|error| format!("Failed to generate secret id: {error}"))?;
+    Ok(encode_hex(&bytes))
+}
+
+/// Whether passwords will go to the vault rather than the Secret Service.
+pub fn uses_vault() -> bool {
+    #[cfg(target_os = "linux")]
+    return !secret_service_available();
+    #[cfg(target_os = "windows")]
+    return true;
+}
+
+#[cfg(target_os = "linux")]
+fn secret_service_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        let bus = std::env::var("DBUS_SESSION_BUS_ADDRESS").is_ok_and(|value| !value.is_empty());
+        // Without arguments it only prints its usage.
+        let installed = match std::process::Command::new("secret-tool")
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+        {
+            Ok(_) => true,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => false,
+            Err(error) => {
+                log::warn!("[secrets] failed to run secret-tool: {error}");
+                false
+            }
+        };
+        log::info!("[secrets] Secret Service available: {}", bus && installed);
+        bus && installed
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn secret_service_store(id: &str, password: &str) -> Result<(), String> {
+    use std::{
+        io::Write,
+        process::{Command, Stdio},
+    };
+
+    let mut child = Command::new("secret-tool")
+        .args([
+            "store",
+            "--label",
+            &format!("TrustTunnel credential {id}"),
+            "service",
+            "trusttunnel-ui",
+            "credential",
+            id,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("failed to run secret-tool: {error}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(password.as_bytes())
+            .map_err(|error| format!("failed to pass secret to secret-tool: {error}"))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|error| format!("secret-tool failed: {error}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "secret-tool store failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn secret_service_lookup(id: &str) -> Result<String, String> {
+    let (success, output) = run_silent_with_output(
+        "secret-tool",
+        &["lookup", "service", "trusttunnel-ui", "credential", id],
+    );
+    if success {
+        Ok(output)
+    } else {
+        Err(format!("secret {id} not found in the Secret Service"))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+struct VaultKey {
+    salt: Vec<u8>,
+    key: [u8; 32],
+}
+
+static VAULT_KEY: Mutex<Option<VaultKey>> = Mutex::new(None);
+
+fn vault_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("trusttunnel")
+        .join("vault.json")
+}
+
+pub fn vault_exists() -> bool {
+    vault_path().exists()
+}
+
+pub fn vault_unlocked() -> bool {
+    VAULT_KEY.lock().is_ok_and(|key| key.is_some())
+}
+
+/// Unlocks the vault, or creates it with this passphrase if there is none yet.
+/// Creating one takes the passphrase a second time as `confirmation`, so a
+/// typo does not lock the saved passwords away.
+pub fn unlock_vault(passphrase: &str, confirmation: &str) -> Result<(), String> {
+    if passphrase.is_empty() {
+        return Err("Vault passphrase must not be empty".into());
+    }
+
+    let path = vault_path();
+    let vault_key = match read_vault_file()? {
+        Some(file) => {
+            let salt = decode_hex(&file.salt)?;
+            let vault_key = VaultKey {
+                key: derive_key(passphrase, &salt)?,
+                salt,
+            };
+            decrypt(&file, &vault_key).map_err(|_| "Wrong vault passphrase".to_string())?;
+            vault_key
+        }
+        None => {
+            if confirmation != passphrase {
+                return Err("The vault passphrases do not match".into());
+            }
+            let mut salt = vec![0u8; SALT_LENGTH];
+            getrandom::fill(&mut salt)
+                .map_err(|error| format!("Failed to generate vault salt: {error}"))?;
+            let vault_key = VaultKey {
+                key: derive_key(passphrase, &salt)?,
+                salt,
+            };
+            write_vault_file(&BTreeMap::new(), &vault_key)?;
+            log::info!("[secrets] created vault {}", path.display());
+            vault_key
+        }
+    };
+
+    if let Ok(mut key) = VAULT_KEY.lock() {
+        *key = Some(vault_key);
+    }
+    log::info!("[secrets] vault unlocked");
+    Ok(())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|error| format!("Failed to derive vault key: {error}"))?;
+    Ok(key)
+}
+
+fn read_vault_file() -> Result<Option<VaultFile>, String> {
+    let path = vault_path();
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(format!("Failed to read {}: {error}", path.display())),
+    };
+    let file: VaultFile = serde_json::from_str(&content)
+        .map_err(|error| format!("Failed to parse {}: {error}", path.display()))?;
+    if file.version != VAULT_FORMAT_VERSION {
+        return Err(format!("Unsupported vault version {}", file.version));
+    }
+    Ok(Some(file))
+}
+
+fn decrypt(file: &VaultFile, vault_key: &VaultKey) -> Result<BTreeMap<String, String>, String> {
+    let nonce = decode_hex(&file.nonce)?;
+    let ciphertext = decode_hex(&file.ciphertext)?;
+    if nonce.len() != NONCE_LENGTH {
+        return Err("Corrupted vault nonce".into());
+    }
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&vault_key.key));
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: file.salt.as_bytes(),
+            },
+        )
+        .map_err(|_| "Failed to decrypt the vault".to_string())?;
+    serde_json::from_slice(&plaintext).map_err(|error| format!("Corrupted vault: {error}"))
+}
+
+fn write_vault_file(
+    secrets: &BTreeMap<String, String>,
+    vault_key: &VaultKey,
+) -> Result<(), String> {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    getrandom::fill(&mut nonce)
+        .map_err(|error| format!("Failed to generate vault nonce: {error}"))?;
+    let salt = encode_hex(&vault_key.salt);
+    let plaintext = serde_json::to_vec(secrets)
+        .map_err(|error| format!("Failed to serialize vault: {error}"))?;
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&vault_key.key));
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &plaintext,
+                aad: salt.as_bytes(),
+            },
+        )
+        .map_err(|_| "Failed to encrypt the vault".to_string())?;
+
+    let file = VaultFile {
+        version: VAULT_FORMAT_VERSION,
+        salt,
+        nonce: encode_hex(&nonce),
+        ciphertext: encode_hex(&ciphertext),
+    };
+    let content = serde_json::to_string_pretty(&file)
+        .map_err(|error| format!("Failed to serialize vault: {error}"))?;
+    crate::configuration::write_private_file(&vault_path(), &content)
+}
+
+/// Tries the passphrase from the environment once when the vault is still locked.
+fn ensure_unlocked() -> Result<(), String> {
+    if vault_unlocked() {
+        return Ok(());
+    }
+    static TRIED_ENVIRONMENT: OnceLock<()> = OnceLock::new();
+    if TRIED_ENVIRONMENT.set(()).is_ok()
+        && let Ok(passphrase) = std::env::var(VAULT_PASSPHRASE_VARIABLE)
+    {
+        return unlock_vault(&passphrase, &passphrase);
+    }
+    Err("The credential vault is locked".into())
+}
+
+fn vault_read() -> Result<BTreeMap<String, String>, String> {
+    ensure_unlocked()?;
+    let key = VAULT_KEY
+        .lock()
+        .map_err(|_| "Vault key poisoned".to_string())?;
+    let vault_key = key.as_ref().ok_or("The credential vault is locked")?;
+    match read_vault_file()? {
+        Some(file) if decode_hex(&file.salt)? == vault_key.salt => decrypt(&file, vault_key),
+        Some(_) => Err("The vault was recreated, unlock it again".into()),
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+fn vault_update(change: impl FnOnce(&mut BTreeMap<String, String>)) -> Result<(), String> {
+    let mut secrets = vault_read()?;
+    change(&mut secrets);
+    let key = VAULT_KEY
+        .lock()
+        .map_err(|_| "Vault key poisoned".to_string())?;
+    let vault_key = key.as_ref().ok_or("The credential vault is locked")?;
+    write_vault_file(&secrets, vault_key)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode_hex(text: &str) -> Result<Vec<u8>, String> {
+    if !text.is_ascii() || !text.len().is_multiple_of(2) {
+        return Err("Corrupted vault: odd hex length".into());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| {
+            u8::from_str_radix(&text[index..index + 2], 16)
+                .map_err(|_| "Corrupted vault: invalid hex".to_string())
+        })
+        .collect()
+}
@@ -0,0 +1,70 @@
+use std::{
+    fs::{File, OpenOptions},
+    os::fd::AsRawFd,
+    path::PathBuf,
+};
+
+use serde_json::json;
+
+use crate::control_socket;
+
+pub struct SingleInstanceGuard {
+    _lock: Option<File>,
+}
+
+/// Takes an exclusive `flock` on a lock file next to the control socket.
+/// Returns `None` when another instance holds it.
+pub fn acquire() -> Option<SingleInstanceGuard> {
+    let path = match control_socket::runtime_directory() {
+        Ok(directory) => directory.join("instance.lock"),
+        Err(error) => {
+            log::warn!("[single_instance] {error}");
+            return Some(SingleInstanceGuard { _lock: None });
+        }
+    };
+
+    let file = match OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+    {
+        Ok(file) => file,
+        Err(error) => {
+            log::warn!(
+                "[single_instance] failed to open {}: {error}",
+                path.display()
+            );
+            return Some(SingleInstanceGuard { _lock: None });
+        }
+    };
+
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        let error = std::io::Error::last_os_error();
+        if error.raw_os_error() == Some(libc::EWOULDBLOCK) {
+            return None;
+        }
+        log::warn!("[single_instance] flock {} failed: {error}", path.display());
+        return Some(SingleInstanceGuard { _lock: None });
+    }
+
+    Some(SingleInstanceGuard { _lock: Some(file) })
+}
+
+/// Hands the credential file argument (if any) to the running instance and
+/// asks it to raise its window.
+pub fn forward_to_running_instance(credential_argument: Option<&String>) -> Result<(), String> {
+    let request = match credential_argument {
+        Some(argument) => {
+            let path = std::path::absolute(PathBuf::from(argument))
+                .map_err(|error| format!("invalid path '{argument}': {error}"))?;
+            log::info!(
+                "[single_instance] forwarding {} to the running instance",
+                path.display()
+            );
+            json!({ "command": "import", "path": path })
+        }
+        None => json!({ "command": "focus" }),
+    };
+    control_socket::send(request).map(|_| ())
+}
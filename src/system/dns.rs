@@ -1,5 +1,5 @@
 #[cfg(target_os = "linux")]
-use super::{resolvconf, resolved, run_silent, run_silent_with_output};
+use super::{journal, resolvconf, resolved, run_silent, run_silent_with_output};
 
 #[cfg(target_os = "windows")]
 use super::{powershell_dns, run_silent};
@@ -68,6 +68,9 @@ pub fn emergency_clear() {
         log::error!("[dns] emergency: retrying with pkexec resolvconf -d tun-trusttunnel");
         let _ = run_silent("pkexec", &["resolvconf", "-d", "tun-trusttunnel"]);
     }
+
+    journal::forget("resolved");
+    journal::forget("resolvconf");
 }
 
 #[cfg(target_os = "windows")]
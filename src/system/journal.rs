@@ -0,0 +1,267 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    linux,
+    proxy::{GsettingsSnapshot, KioSnapshot},
+    resolvconf, resolved,
+};
+
+/// One system override applied by this process, with what is needed to undo it.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "kebab-case")]
+pub enum JournalEntry {
+    Resolved { interface: String },
+    Resolvconf,
+    Gsettings { snapshot: GsettingsSnapshot },
+    Kio { snapshot: KioSnapshot },
+}
+
+impl JournalEntry {
+    fn backend(&self) -> &'static str {
+        match self {
+            Self::Resolved { .. } => "resolved",
+            Self::Resolvconf => "resolvconf",
+            Self::Gsettings { .. } => "gsettings",
+            Self::Kio { .. } => "kio",
+        }
+    }
+
+    fn revert(&self) {
+        match self {
+            Self::Resolved { interface } => resolved::revert_stale(interface),
+            Self::Resolvconf => resolvconf::revert_stale(),
+            Self::Gsettings { snapshot } => snapshot.restore(),
+            Self::Kio { snapshot } => snapshot.restore(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Journal {
+    process_id: u32,
+    /// Boot and start time of the recording process, so that after a reboot
+    /// or with the id reused the overrides are not mistaken for live ones.
+    #[serde(default)]
+    boot_id: Option<String>,
+    #[serde(default)]
+    process_start_time: Option<u64>,
+    entries: Vec<JournalEntry>,
+}
+
+impl Journal {
+    /// Checked by boot and start time before the process id, since a stale
+    /// journal can carry the id this process got after a reboot.
+    fn owner_is_running(&self) -> bool {
+        if self
+            .boot_id
+            .as_ref()
+            .is_some_and(|boot_id| linux::boot_id().as_ref() != Some(boot_id))
+        {
+            return false;
+        }
+        match (
+            self.process_start_time,
+            linux::process_start_time(self.process_id),
+        ) {
+            (_, None) => false,
+            (Some(recorded), Some(current)) => recorded == current,
+            // This process always records its start time, so an unmarked
+            // journal under its own id was left by an earlier one.
+            (None, Some(_)) => self.process_id != std::process::id(),
+        }
+    }
+}
+
+static ENTRIES: Mutex<Vec<JournalEntry>> = Mutex::new(Vec::new());
+
+fn journal_directory() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("trusttunnel")
+        .join("journal")
+}
+
+fn journal_path(process_id: u32) -> PathBuf {
+    journal_directory().join(format!("{process_id}.json"))
+}
+
+/// Records an override before it is applied. An existing entry for the same
+/// backend is kept, since it holds the values from before the first change.
+pub fn record(entry: JournalEntry) {
+    let Ok(mut entries) = ENTRIES.lock() else {
+        return;
+    };
+    if entries
+        .iter()
+        .any(|existing| existing.backend() == entry.backend())
+    {
+        return;
+    }
+    if entries.is_empty() {
+        replay_reused_id();
+    }
+    entries.push(entry);
+    write(&entries);
+}
+
+/// Reverts a journal left under this process id by an earlier process,
+/// before the first write of this one replaces it.
+fn replay_reused_id() {
+    let path = journal_path(std::process::id());
+    if !path.exists() {
+        return;
+    }
+    if let Some(journal) = read_journal(&path)
+        && !journal.owner_is_running()
+    {
+        revert_journal(&path, &journal);
+    }
+}
+
+/// Whether this process has an override of `backend` to undo.
+pub fn contains(backend: &str) -> bool {
+    ENTRIES
+        .lock()
+        .is_ok_and(|entries| entries.iter().any(|entry| entry.backend() == backend))
+}
+
+/// Removes and returns the entry for `backend`, e.g. to restore its snapshot.
+pub fn take(backend: &str) -> Option<JournalEntry> {
+    let mut entries = ENTRIES.lock().ok()?;
+    let position = entries
+        .iter()
+        .position(|entry| entry.backend() == backend)?;
+    let entry = entries.remove(position);
+    write(&entries);
+    Some(entry)
+}
+
+/// Drops the entry for `backend` once its override has been reverted.
+pub fn forget(backend: &str) {
+    let Ok(mut entries) = ENTRIES.lock() else {
+        return;
+    };
+    let count = entries.len();
+    entries.retain(|entry| entry.backend() != backend);
+    if entries.len() != count {
+        write(&entries);
+    }
+}
+
+fn write(entries: &[JournalEntry]) {
+    let process_id = std::process::id();
+    let path = journal_path(process_id);
+
+    if entries.is_empty() {
+        if let Err(error) = std::fs::remove_file(&path)
+            && error.kind() != std::io::ErrorKind::NotFound
+        {
+            log::warn!("[journal] failed to remove {}: {error}", path.display());
+        }
+        return;
+    }
+
+    let journal = Journal {
+        process_id,
+        boot_id: linux::boot_id(),
+        process_start_time: linux::process_start_time(process_id),
+        entries: entries.to_vec(),
+    };
+    let content = match serde_json::to_string_pretty(&journal) {
+        Ok(content) => content,
+        Err(error) => {
+            log::warn!("[journal] failed to serialize: {error}");
+            return;
+        }
+    };
+    if let Err(error) = std::fs::create_dir_all(journal_directory()) {
+        log::warn!("[journal] failed to create directory: {error}");
+        return;
+    }
+    // Snapshots of kioslaverc can carry proxy credentials.
+    let temporary_path = path.with_extension("json.tmp");
+    if let Err(error) = crate::configuration::write_private_file(&temporary_path, &content)
+        .and_then(|_| std::fs::rename(&temporary_path, &path).map_err(|error| error.to_string()))
+    {
+        log::warn!("[journal] failed to write {}: {error}", path.display());
+    }
+}
+
+fn read_journal(path: &Path) -> Option<Journal> {
+    match std::fs::read_to_string(path)
+        .map_err(|error| error.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|error| error.to_string()))
+    {
+        Ok(journal) => Some(journal),
+        Err(error) => {
+            log::warn!(
+                "[journal] discarding unreadable {}: {error}",
+                path.display()
+            );
+            let _ = std::fs::remove_file(path);
+            None
+        }
+    }
+}
+
+fn revert_journal(path: &Path, journal: &Journal) {
+    for stale in journal.entries.iter().rev() {
+        log::warn!(
+            "[journal] reverting {} override left by process {}",
+            stale.backend(),
+            journal.process_id
+        );
+        stale.revert();
+    }
+    if let Err(error) = std::fs::remove_file(path) {
+        log::warn!("[journal] failed to remove {}: {error}", path.display());
+    }
+}
+
+/// Reverts the overrides another process recorded, e.g. a headless session
+/// owner that did not clean up in time. Returns whether it had a journal.
+pub fn replay_process(process_id: u32) -> bool {
+    let path = journal_path(process_id);
+    if !path.exists() {
+        return false;
+    }
+    match read_journal(&path) {
+        Some(journal) => {
+            revert_journal(&path, &journal);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Reverts overrides left behind by processes that are no longer running.
+pub fn replay_stale() {
+    let Ok(directory) = std::fs::read_dir(journal_directory()) else {
+        return;
+    };
+
+    for entry in directory.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(journal) = read_journal(&path) else {
+            continue;
+        };
+
+        if journal.owner_is_running() {
+            log::info!(
+                "[journal] process {} is still running, leaving its overrides",
+                journal.process_id
+            );
+            continue;
+        }
+
+        revert_journal(&path, &journal);
+    }
+}
@@ -2,6 +2,10 @@ use std::{
     io,
     path::Path,
     process::{Command, Stdio},
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
 use super::{ChildProcess, run_silent, run_silent_with_output};
@@ -16,6 +20,49 @@ pub fn elevate_terminate_process(process_id: u32) -> bool {
     run_silent("pkexec", &["kill", "-INT", &process_id_string])
 }
 
+pub fn is_process_running(process_id: u32) -> bool {
+    Path::new(&format!("/proc/{process_id}")).exists()
+}
+
+/// Changes on every boot, so it tells a recorded process id from a reused one.
+pub fn boot_id() -> Option<String> {
+    std::fs::read_to_string("/proc/sys/kernel/random/boot_id")
+        .ok()
+        .map(|id| id.trim().to_string())
+}
+
+/// Start time of the process in clock ticks since boot, field 22 of
+/// `/proc/<pid>/stat`. The command name before it may contain spaces.
+pub fn process_start_time(process_id: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{process_id}/stat")).ok()?;
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(19)?.parse().ok()
+}
+
+pub fn is_root() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+pub fn install_interrupt_handler(flag: &'static AtomicBool) {
+    static FLAG: OnceLock<&'static AtomicBool> = OnceLock::new();
+
+    extern "C" fn handler(_signal: libc::c_int) {
+        if let Some(flag) = FLAG.get() {
+            flag.store(true, Ordering::SeqCst);
+        }
+    }
+
+    if FLAG.set(flag).is_err() {
+        return;
+    }
+    // A handler (unlike SIG_IGN) is reset on exec, so the client still sees
+    // the terminal's SIGINT and shuts down on its own.
+    unsafe {
+        libc::signal(libc::SIGINT, handler as *const () as libc::sighandler_t);
+        libc::signal(libc::SIGTERM, handler as *const () as libc::sighandler_t);
+    }
+}
+
 pub fn spawn_client(
     binary: &str,
     configuration_path: &Path,
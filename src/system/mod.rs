@@ -1,7 +1,10 @@
 use std::{
     io,
     process::{Child, Command, Stdio},
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
 #[cfg(target_os = "windows")]
@@ -10,6 +13,9 @@ use std::path::PathBuf;
 pub mod dns;
 pub mod proxy;
 
+#[cfg(target_os = "linux")]
+pub(crate) mod journal;
+
 #[cfg(target_os = "linux")]
 mod linux;
 
@@ -40,6 +46,8 @@ pub trait SystemServices: Send + Sync {
 
     fn elevate_terminate_process(&self, process_id: u32) -> bool;
 
+    fn is_process_running(&self, process_id: u32) -> bool;
+
     fn find_client_binary(&self) -> (String, bool);
 
     fn check_tun_device(&self) -> bool;
@@ -56,6 +64,17 @@ pub trait SystemServices: Send + Sync {
         proxy::emergency_clear();
         dns::emergency_clear();
     }
+
+    /// Undoes the proxy and DNS overrides of another process that did not
+    /// clean up after itself, e.g. a headless session owner.
+    fn revert_process_overrides(&self, _process_id: u32, proxy: bool, dns: bool) {
+        if proxy {
+            proxy::emergency_clear();
+        }
+        if dns {
+            dns::emergency_clear();
+        }
+    }
 }
 
 #[cfg(target_os = "linux")]
@@ -93,6 +112,10 @@ impl SystemServices for LinuxSystem {
         linux::elevate_terminate_process(process_id)
     }
 
+    fn is_process_running(&self, process_id: u32) -> bool {
+        linux::is_process_running(process_id)
+    }
+
     fn find_client_binary(&self) -> (String, bool) {
         linux::find_client_binary()
     }
@@ -104,6 +127,17 @@ impl SystemServices for LinuxSystem {
     fn check_elevation_available(&self) -> bool {
         linux::check_elevation_available()
     }
+
+    fn startup_cleanup(&self) {
+        journal::replay_stale();
+    }
+
+    /// The journal holds the owner's snapshots; this process has none of its own.
+    fn revert_process_overrides(&self, process_id: u32, _proxy: bool, _dns: bool) {
+        if !journal::replay_process(process_id) {
+            log::info!("[system] process {process_id} left no overrides to revert");
+        }
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -125,6 +159,10 @@ impl SystemServices for WindowsSystem {
         windows::elevate_terminate_process(process_id)
     }
 
+    fn is_process_running(&self, process_id: u32) -> bool {
+        windows::is_process_running(process_id)
+    }
+
     fn find_client_binary(&self) -> (String, bool) {
         windows::find_client_binary()
     }
@@ -151,6 +189,30 @@ impl SystemServices for WindowsSystem {
     }
 }
 
+/// Set by the interrupt handler of a headless session.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Catches Ctrl+C / SIGTERM so a headless session can restore the system
+/// before exiting instead of being killed outright.
+pub fn install_interrupt_handler() {
+    #[cfg(target_os = "linux")]
+    linux::install_interrupt_handler(&INTERRUPTED);
+    #[cfg(target_os = "windows")]
+    windows::install_interrupt_handler(&INTERRUPTED);
+}
+
+/// Whether the current process can bring up TUN without elevation.
+pub fn is_privileged() -> bool {
+    #[cfg(target_os = "linux")]
+    return linux::is_root();
+    #[cfg(target_os = "windows")]
+    return windows::is_running_as_admin();
+}
+
 pub struct ChildExit {
     pub code: Option<i32>,
 }
@@ -394,30 +456,3 @@ pub fn check_binary_works(binary: &str, needs_root: bool) -> Option<String> {
         }
     }
 }
-
-pub fn parse_host_port(address: &str) -> (String, u16) {
-    // Handle [IPv6]:port bracket notation (e.g. "[::1]:1080")
-    if address.starts_with('[')
-        && let Some(bracket_end) = address.find(']')
-    {
-        let host = &address[1..bracket_end];
-        let port = address[bracket_end + 1..]
-            .strip_prefix(':')
-            .and_then(|p| p.parse::<u16>().ok())
-            .unwrap_or(1080);
-        return (host.to_string(), port);
-    }
-
-    if let Some(index) = address.rfind(':') {
-        let before_colon = &address[..index];
-        // If there is another colon before this one it is a bare IPv6 address
-        // without an explicit port (e.g. "::1" or "2001:db8::1").
-        if before_colon.contains(':') {
-            return (address.to_string(), 1080);
-        }
-        let port = address[index + 1..].parse::<u16>().unwrap_or(1080);
-        (before_colon.to_string(), port)
-    } else {
-        (address.to_string(), 1080)
-    }
-}
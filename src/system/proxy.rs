@@ -2,11 +2,23 @@
 use std::path::PathBuf;
 
 #[cfg(target_os = "linux")]
-use super::{run_silent, run_silent_with_output};
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "linux")]
+use super::{
+    journal::{self, JournalEntry},
+    run_silent, run_silent_with_output,
+};
+use crate::configuration::ProxyCredentials;
 
 pub trait ProxyBackend: Send {
     fn name(&self) -> &str;
-    fn set(&mut self, host: &str, port: u16) -> Result<String, String>;
+    fn set(
+        &mut self,
+        host: &str,
+        port: u16,
+        credentials: Option<&ProxyCredentials>,
+    ) -> Result<String, String>;
     fn clear(&mut self);
 }
 
@@ -45,12 +57,16 @@ pub fn detect() -> Vec<Box<dyn ProxyBackend>> {
     vec![Box::new(super::windows::RegistryProxy)]
 }
 
-pub fn set_all(host: &str, port: u16) -> (Vec<Box<dyn ProxyBackend>>, String) {
+pub fn set_all(
+    host: &str,
+    port: u16,
+    credentials: Option<&ProxyCredentials>,
+) -> (Vec<Box<dyn ProxyBackend>>, String) {
     let mut backends = detect();
     let mut details: Vec<String> = Vec::new();
 
     for backend in &mut backends {
-        match backend.set(host, port) {
+        match backend.set(host, port, credentials) {
             Ok(detail) => {
                 log::info!("[proxy] {} set OK: {detail}", backend.name());
                 details.push(detail);
@@ -78,12 +94,18 @@ pub fn clear_all(backends: &mut [Box<dyn ProxyBackend>]) {
     }
 }
 
-/// Does not rely on stored backend state — tries every known mechanism.
+/// Does not rely on stored backend state — restores every backend this process
+/// journaled. Backends it never touched are left alone, since resetting them
+/// would wipe a proxy the user configured.
 #[cfg(target_os = "linux")]
 pub fn emergency_clear() {
-    log::error!("[proxy] emergency proxy cleanup — trying all known backends");
-    GnomeProxy.clear();
-    KdeProxy.clear();
+    log::error!("[proxy] emergency proxy cleanup — restoring journaled backends");
+    if journal::contains("gsettings") {
+        GnomeProxy.clear();
+    }
+    if journal::contains("kio") {
+        KdeProxy.clear();
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -108,13 +130,94 @@ impl GnomeProxy {
     }
 }
 
+#[cfg(target_os = "linux")]
+const GSETTINGS_PROXY_SCHEMA: &str = "org.gnome.system.proxy";
+
+#[cfg(target_os = "linux")]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct GsettingsValue {
+    schema: String,
+    key: String,
+    value: String,
+}
+
+/// Every key of `org.gnome.system.proxy` and its child schemas, as GVariant text.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GsettingsSnapshot {
+    values: Vec<GsettingsValue>,
+}
+
+#[cfg(target_os = "linux")]
+impl GsettingsSnapshot {
+    pub fn capture() -> Option<Self> {
+        let (success, output) =
+            run_silent_with_output("gsettings", &["list-recursively", GSETTINGS_PROXY_SCHEMA]);
+        if !success {
+            return None;
+        }
+
+        let values: Vec<GsettingsValue> = output
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, ' ');
+                Some(GsettingsValue {
+                    schema: fields.next()?.to_string(),
+                    key: fields.next()?.to_string(),
+                    value: fields.next()?.to_string(),
+                })
+            })
+            .collect();
+        (!values.is_empty()).then_some(Self { values })
+    }
+
+    /// Writes back only the keys that differ, with `mode` last so the proxy is
+    /// never enabled against half-restored hosts.
+    pub fn restore(&self) {
+        let current = Self::capture().map(|snapshot| snapshot.values);
+        let (mode, others): (Vec<_>, Vec<_>) = self
+            .values
+            .iter()
+            .filter(|saved| {
+                current
+                    .as_ref()
+                    .is_none_or(|current| !current.contains(saved))
+            })
+            .partition(|saved| saved.schema == GSETTINGS_PROXY_SCHEMA && saved.key == "mode");
+
+        for saved in others.into_iter().chain(mode) {
+            log::debug!(
+                "[proxy] GSettings: restoring {} {} = {}",
+                saved.schema,
+                saved.key,
+                saved.value
+            );
+            if !run_silent(
+                "gsettings",
+                &["set", &saved.schema, &saved.key, &saved.value],
+            ) {
+                log::warn!(
+                    "[proxy] GSettings: failed to restore {} {}",
+                    saved.schema,
+                    saved.key
+                );
+            }
+        }
+    }
+}
+
 #[cfg(target_os = "linux")]
 impl ProxyBackend for GnomeProxy {
     fn name(&self) -> &str {
         "GSettings"
     }
 
-    fn set(&mut self, host: &str, port: u16) -> Result<String, String> {
+    fn set(
+        &mut self,
+        host: &str,
+        port: u16,
+        credentials: Option<&ProxyCredentials>,
+    ) -> Result<String, String> {
         let port_string = port.to_string();
 
         log::info!(
@@ -123,6 +226,14 @@ impl ProxyBackend for GnomeProxy {
             port,
         );
 
+        match GsettingsSnapshot::capture() {
+            Some(snapshot) => journal::record(JournalEntry::Gsettings { snapshot }),
+            None => log::warn!(
+                "[proxy] GSettings: failed to snapshot current settings, \
+                 disconnecting will reset the proxy mode to none"
+            ),
+        }
+
         run_silent(
             "gsettings",
             &["set", "org.gnome.system.proxy.socks", "host", host],
@@ -184,11 +295,32 @@ impl ProxyBackend for GnomeProxy {
             verify_same.trim(),
         );
 
-        let detail = format!("System proxy configured via GSettings (SOCKS5 {host}:{port})",);
+        // org.gnome.system.proxy.socks has no authentication keys.
+        let detail = if credentials.is_some() {
+            log::warn!(
+                "[proxy] GSettings cannot store SOCKS5 credentials, \
+                 applications using it will fail to authenticate"
+            );
+            format!(
+                "System proxy configured via GSettings (SOCKS5 {host}:{port}, \
+                 credentials not supported — disable SOCKS authentication for GNOME apps)"
+            )
+        } else {
+            format!("System proxy configured via GSettings (SOCKS5 {host}:{port})")
+        };
         Ok(detail)
     }
 
     fn clear(&mut self) {
+        if let Some(JournalEntry::Gsettings { snapshot }) = journal::take("gsettings") {
+            log::info!("[proxy] GSettings: restoring previous proxy settings");
+            snapshot.restore();
+            let (_, verify_mode) =
+                run_silent_with_output("gsettings", &["get", "org.gnome.system.proxy", "mode"]);
+            log::info!("[proxy] GSettings restored — mode={}", verify_mode.trim());
+            return;
+        }
+
         log::info!("[proxy] GSettings: clearing proxy settings");
 
         run_silent(
@@ -244,6 +376,86 @@ impl KdeProxy {
             ],
         );
     }
+
+    fn write_proxy_type(proxy_type: &str) {
+        let kioslaverc_string = Self::kioslaverc_path().to_string_lossy().to_string();
+        run_silent(
+            Self::kwriteconfig_command(),
+            &[
+                "--file",
+                &kioslaverc_string,
+                "--group",
+                "Proxy Settings",
+                "--key",
+                "ProxyType",
+                proxy_type,
+            ],
+        );
+    }
+}
+
+#[cfg(target_os = "linux")]
+const KIO_PROXY_GROUP: &str = "[Proxy Settings]";
+
+/// The raw `[Proxy Settings]` group of kioslaverc, `None` if it did not exist.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct KioSnapshot {
+    group: Option<Vec<String>>,
+}
+
+#[cfg(target_os = "linux")]
+impl KioSnapshot {
+    pub fn capture() -> Self {
+        let content = std::fs::read_to_string(KdeProxy::kioslaverc_path()).unwrap_or_default();
+        let (_, group, _) = split_kio_group(&content);
+        Self {
+            group: group.map(|lines| lines.into_iter().map(str::to_string).collect()),
+        }
+    }
+
+    /// Puts the saved group back in place of whatever is there now.
+    pub fn restore(&self) {
+        let path = KdeProxy::kioslaverc_path();
+        let content = std::fs::read_to_string(&path).unwrap_or_default();
+        let (before, _, after) = split_kio_group(&content);
+
+        let mut lines: Vec<&str> = before;
+        if let Some(ref group) = self.group {
+            lines.extend(group.iter().map(String::as_str));
+        }
+        lines.extend(after);
+
+        let mut restored = lines.join("\n");
+        if !restored.is_empty() {
+            restored.push('\n');
+        }
+        if let Err(error) = std::fs::write(&path, restored) {
+            log::warn!("[proxy] KDE: failed to restore {}: {error}", path.display());
+            return;
+        }
+        KdeProxy::notify_kio();
+    }
+}
+
+/// Splits kioslaverc into the lines before, inside (header included) and after
+/// the proxy group.
+#[cfg(target_os = "linux")]
+fn split_kio_group(content: &str) -> (Vec<&str>, Option<Vec<&str>>, Vec<&str>) {
+    let lines: Vec<&str> = content.lines().collect();
+    let Some(start) = lines.iter().position(|line| line.trim() == KIO_PROXY_GROUP) else {
+        return (lines, None, Vec::new());
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .map_or(lines.len(), |offset| start + 1 + offset);
+
+    (
+        lines[..start].to_vec(),
+        Some(lines[start..end].to_vec()),
+        lines[end..].to_vec(),
+    )
 }
 
 #[cfg(target_os = "linux")]
@@ -252,29 +464,39 @@ impl ProxyBackend for KdeProxy {
         "KDE KIO"
     }
 
-    fn set(&mut self, host: &str, port: u16) -> Result<String, String> {
+    fn set(
+        &mut self,
+        host: &str,
+        port: u16,
+        credentials: Option<&ProxyCredentials>,
+    ) -> Result<String, String> {
         let kwriteconfig = Self::kwriteconfig_command();
         let kioslaverc = Self::kioslaverc_path();
         let kioslaverc_string = kioslaverc.to_string_lossy().to_string();
-        let proxy_url = format!("socks5://{host}:{port}");
+        let host_port = if host.contains(':') {
+            format!("[{host}]:{port}")
+        } else {
+            format!("{host}:{port}")
+        };
+        let proxy_url = match credentials {
+            Some(credentials) => format!(
+                "socks5://{}:{}@{host_port}",
+                credentials.username, credentials.password
+            ),
+            None => format!("socks5://{host_port}"),
+        };
 
         log::info!(
-            "[proxy] KDE: setting SOCKS5 proxy to {proxy_url} \
-             (kwriteconfig={kwriteconfig}, kioslaverc={kioslaverc_string})",
+            "[proxy] KDE: setting SOCKS5 proxy to socks5://{host_port} \
+             (authentication={}, kwriteconfig={kwriteconfig}, kioslaverc={kioslaverc_string})",
+            credentials.is_some(),
         );
 
-        run_silent(
-            kwriteconfig,
-            &[
-                "--file",
-                &kioslaverc_string,
-                "--group",
-                "Proxy Settings",
-                "--key",
-                "ProxyType",
-                "1",
-            ],
-        );
+        journal::record(JournalEntry::Kio {
+            snapshot: KioSnapshot::capture(),
+        });
+
+        Self::write_proxy_type("1");
         run_silent(
             kwriteconfig,
             &[
@@ -316,30 +538,24 @@ impl ProxyBackend for KdeProxy {
 
         Self::notify_kio();
 
-        let detail = format!("System proxy configured via KDE KIO (SOCKS5 {host}:{port})",);
+        let detail = if credentials.is_some() {
+            format!("System proxy configured via KDE KIO (SOCKS5 {host_port}, authenticated)")
+        } else {
+            format!("System proxy configured via KDE KIO (SOCKS5 {host_port})")
+        };
         Ok(detail)
     }
 
     fn clear(&mut self) {
-        log::info!("[proxy] KDE: clearing proxy settings");
-
-        let kwriteconfig = Self::kwriteconfig_command();
-        let kioslaverc = Self::kioslaverc_path();
-        let kioslaverc_string = kioslaverc.to_string_lossy().to_string();
+        if let Some(JournalEntry::Kio { snapshot }) = journal::take("kio") {
+            log::info!("[proxy] KDE: restoring previous proxy settings");
+            snapshot.restore();
+            return;
+        }
 
-        run_silent(
-            kwriteconfig,
-            &[
-                "--file",
-                &kioslaverc_string,
-                "--group",
-                "Proxy Settings",
-                "--key",
-                "ProxyType",
-                "0",
-            ],
-        );
+        log::info!("[proxy] KDE: clearing proxy settings");
 
+        Self::write_proxy_type("0");
         Self::notify_kio();
 
         log::info!("[proxy] KDE: proxy type reset to 0");
@@ -2,6 +2,7 @@ use std::process::{Command, Stdio};
 
 use super::{
     dns::{self, DnsBackend},
+    journal::{self, JournalEntry},
     run_silent, run_silent_with_output,
 };
 
@@ -46,6 +47,8 @@ impl DnsBackend for ResolvconfDns {
             .collect::<Vec<_>>()
             .join("\n");
 
+        journal::record(JournalEntry::Resolvconf);
+
         if try_resolvconf_set(&stdin_content) {
             let detail = format!("DNS configured via resolvconf ({dns_servers_string})");
             log::info!("[dns] {detail}");
@@ -61,6 +64,7 @@ impl DnsBackend for ResolvconfDns {
             return Ok(detail);
         }
 
+        journal::forget("resolvconf");
         let detail = "Failed to set DNS via resolvconf (tried both direct and pkexec)".to_string();
         log::warn!("[dns] {detail}");
         Err(detail)
@@ -69,12 +73,19 @@ impl DnsBackend for ResolvconfDns {
     fn clear(&mut self) {
         log::info!("[dns] clearing DNS via resolvconf");
         if resolvconf_delete() {
+            journal::forget("resolvconf");
             return;
         }
         log::warn!("[dns] resolvconf -d failed");
     }
 }
 
+pub fn revert_stale() {
+    if !resolvconf_delete() {
+        log::warn!("[resolvconf] failed to remove stale {RESOLVCONF_INTERFACE} entry");
+    }
+}
+
 fn try_resolvconf_set(stdin_content: &str) -> bool {
     let result = Command::new("resolvconf")
         .args(["-a", RESOLVCONF_INTERFACE, "-m", "0", "-x"])
@@ -1,5 +1,6 @@
 use super::{
     dns::{self, DnsBackend},
+    journal::{self, JournalEntry},
     run_silent, run_silent_with_output,
 };
 
@@ -49,7 +50,12 @@ impl DnsBackend for ResolvedDns {
         };
         dns_args.extend(servers_slice);
 
+        journal::record(JournalEntry::Resolved {
+            interface: interface.clone(),
+        });
+
         if !resolvectl(&dns_args) {
+            journal::forget("resolved");
             let detail = format!("Failed to set DNS server entries on {interface} via resolvectl");
             log::warn!("[dns] {detail}");
             return Err(detail);
@@ -92,6 +98,7 @@ impl DnsBackend for ResolvedDns {
                     "[dns] no stored/detected TUN interface to revert — \
                      systemd-resolved likely cleaned up automatically"
                 );
+                journal::forget("resolved");
                 return;
             }
         };
@@ -106,6 +113,21 @@ impl DnsBackend for ResolvedDns {
                  (interface may already be destroyed)"
             );
         }
+        journal::forget("resolved");
+    }
+}
+
+/// Per-link settings vanish with the interface, so only a surviving link needs a revert.
+pub fn revert_stale(interface: &str) {
+    if !std::path::Path::new("/sys/class/net")
+        .join(interface)
+        .exists()
+    {
+        log::info!("[resolved] {interface} no longer exists, nothing to revert");
+        return;
+    }
+    if !resolvectl(&["revert", interface]) {
+        log::warn!("[resolved] failed to revert stale DNS on {interface}");
     }
 }
 
@@ -2,9 +2,16 @@ use std::{
     io,
     path::PathBuf,
     process::{Child, Command, Stdio},
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
-use super::{CREATE_NO_WINDOW, ChildProcess, proxy::ProxyBackend, run_silent};
+use super::{
+    CREATE_NO_WINDOW, ChildProcess, proxy::ProxyBackend, run_silent, run_silent_with_output,
+};
+use crate::configuration::ProxyCredentials;
 
 const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
 
@@ -185,6 +192,14 @@ pub fn terminate_process(process_id: u32) -> bool {
     win32::terminate(process_id, 0)
 }
 
+pub fn is_process_running(process_id: u32) -> bool {
+    let filter = format!("PID eq {process_id}");
+    let (_, output) = run_silent_with_output("tasklist", &["/FI", &filter, "/NH"]);
+    output
+        .split_whitespace()
+        .any(|column| column == process_id.to_string())
+}
+
 pub fn elevate_terminate_process(process_id: u32) -> bool {
     log::info!(
         "[process] terminating process_id {} via native API (elevated)",
@@ -273,8 +288,36 @@ fn notify_proxy_settings_changed() {
     }
 }
 
+/// The `ProxyServer` value this UI last wrote, so a stale one can be told
+/// apart from the user's own proxy after the listen address changed.
+fn written_proxy_path() -> std::path::PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join("trusttunnel")
+        .join("system-proxy")
+}
+
+fn proxy_server_value(host: &str, port: u16) -> String {
+    if host.contains(':') && !host.starts_with('[') {
+        format!("socks=[{host}]:{port}")
+    } else {
+        format!("socks={host}:{port}")
+    }
+}
+
 pub fn set_system_proxy(host: &str, port: u16) -> String {
-    let proxy_value = format!("socks={}:{}", host, port);
+    let proxy_value = proxy_server_value(host, port);
+
+    let written_path = written_proxy_path();
+    if let Some(parent) = written_path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(error) = std::fs::write(&written_path, &proxy_value) {
+        log::warn!(
+            "[proxy] failed to record {}: {error}",
+            written_path.display()
+        );
+    }
 
     log::info!("[proxy] setting system SOCKS5 proxy to {}:{}", host, port);
 
@@ -322,6 +365,7 @@ pub fn set_system_proxy(host: &str, port: u16) -> String {
 
 pub fn clear_system_proxy() {
     log::info!("[proxy] clearing system proxy settings");
+    let _ = std::fs::remove_file(written_proxy_path());
 
     match windows_registry::CURRENT_USER.create(INTERNET_SETTINGS_KEY) {
         Ok(key) => {
@@ -360,8 +404,18 @@ impl ProxyBackend for RegistryProxy {
         "Windows Registry"
     }
 
-    fn set(&mut self, host: &str, port: u16) -> Result<String, String> {
-        let detail = set_system_proxy(host, port);
+    fn set(
+        &mut self,
+        host: &str,
+        port: u16,
+        credentials: Option<&ProxyCredentials>,
+    ) -> Result<String, String> {
+        let mut detail = set_system_proxy(host, port);
+        // WinINet has no way to carry SOCKS credentials in ProxyServer.
+        if credentials.is_some() {
+            log::warn!("[proxy] Windows registry proxy cannot carry SOCKS5 credentials");
+            detail.push_str(" — credentials not supported, apps must authenticate themselves");
+        }
         Ok(detail)
     }
 
@@ -582,6 +636,43 @@ pub fn install_ctrl_handler() {
     }
 }
 
+pub fn install_interrupt_handler(flag: &'static AtomicBool) {
+    use windows::{
+        Win32::System::Console::{
+            CTRL_BREAK_EVENT, CTRL_C_EVENT, CTRL_CLOSE_EVENT, SetConsoleCtrlHandler,
+        },
+        core::BOOL,
+    };
+
+    static FLAG: OnceLock<&'static AtomicBool> = OnceLock::new();
+
+    unsafe extern "system" fn handler(ctrl_type: u32) -> BOOL {
+        match ctrl_type {
+            x if x == CTRL_C_EVENT || x == CTRL_BREAK_EVENT || x == CTRL_CLOSE_EVENT => {
+                if let Some(flag) = FLAG.get() {
+                    flag.store(true, Ordering::SeqCst);
+                }
+                BOOL(1)
+            }
+            _ => BOOL(0),
+        }
+    }
+
+    if FLAG.set(flag).is_err() {
+        return;
+    }
+    if let Err(error) = unsafe { SetConsoleCtrlHandler(Some(handler), true) } {
+        log::warn!("[cli] SetConsoleCtrlHandler failed: {error}");
+    }
+}
+
+/// Release builds use the GUI subsystem, so CLI output needs the parent console.
+pub fn attach_parent_console() {
+    use windows::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
 pub fn cleanup_stale_system_proxy() {
     let Ok(key) = windows_registry::CURRENT_USER.open(INTERNET_SETTINGS_KEY) else {
         return;
@@ -591,29 +682,45 @@ pub fn cleanup_stale_system_proxy() {
         return;
     }
     let server: String = key.get_string("ProxyServer").unwrap_or_default();
-    let expected = format!("socks={}", crate::configuration::PROXY_LISTEN_ADDRESS);
-    if server == expected {
-        let client_running = {
-            use std::os::windows::process::CommandExt;
-            Command::new("tasklist")
-                .args(["/FI", "IMAGENAME eq trusttunnel_client.exe", "/NH"])
-                .stdin(Stdio::null())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::null())
-                .creation_flags(CREATE_NO_WINDOW)
-                .output()
-                .map(|output| {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    stdout.contains("trusttunnel_client.exe")
-                })
-                .unwrap_or(false)
-        };
-        if !client_running {
-            log::warn!(
-                "[startup] stale system proxy detected ({}), clearing",
-                server,
-            );
-            clear_system_proxy();
-        }
+    // Older versions left no record; fall back to the configured listener.
+    let expected = std::fs::read_to_string(written_proxy_path())
+        .map(|written| written.trim().to_string())
+        .unwrap_or_else(|_| {
+            let address = crate::app_state::AppState::load()
+                .proxy_listen_address()
+                .to_string();
+            match address.rsplit_once(':') {
+                Some((host, port)) => match port.parse() {
+                    Ok(port) => proxy_server_value(host, port),
+                    Err(_) => format!("socks={address}"),
+                },
+                None => format!("socks={address}"),
+            }
+        });
+    if server != expected {
+        let _ = std::fs::remove_file(written_proxy_path());
+        return;
+    }
+    let client_running = {
+        use std::os::windows::process::CommandExt;
+        Command::new("tasklist")
+            .args(["/FI", "IMAGENAME eq trusttunnel_client.exe", "/NH"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map(|output| {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                stdout.contains("trusttunnel_client.exe")
+            })
+            .unwrap_or(false)
+    };
+    if !client_running {
+        log::warn!(
+            "[startup] stale system proxy detected ({}), clearing",
+            server,
+        );
+        clear_system_proxy();
     }
 }
@@ -59,6 +59,7 @@ pub const LINE_HEIGHT_EXTRA_SMALL: f32 = 14.0;
 pub const ELEMENT_HEIGHT: f32 = 32.0;
 pub const TITLEBAR_HEIGHT: f32 = 32.0;
 pub const TEXTAREA_HEIGHT: f32 = 80.0;
+pub const RELEASE_NOTES_HEIGHT: f32 = 180.0;
 
 // Radius
 pub const RADIUS: f32 = 4.0;
@@ -89,3 +90,6 @@ pub const TOGGLE_HEIGHT: f32 = 18.0;
 pub const TOGGLE_DOT_SIZE: f32 = 14.0;
 pub const TOGGLE_DOT_ON_OFFSET: f32 = 18.0;
 pub const TOGGLE_DOT_OFF_OFFSET: f32 = 2.0;
+
+// Badge
+pub const BADGE_SIZE: f32 = 6.0;